@@ -11,6 +11,74 @@ pub struct Inputs<'a> {
     pub strip_index_format: Option<IndexFormat>,
 }
 
+pub fn create_pipeline(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    inputs: Inputs<'_>,
+) -> wgpu::RenderPipeline {
+    // Load the shaders from disk
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: inputs.source,
+    });
+    
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor { 
+        label: None,
+        bind_group_layouts: &[],
+        push_constant_ranges: &[], 
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState { 
+            module: &shader, 
+            entry_point: "vs_main", 
+            buffers: &[], 
+        },
+        fragment: Some(wgpu::FragmentState { 
+            module: &shader, 
+            entry_point: "fs_main", 
+            targets: &[Some(format.into())], 
+        }),
+        primitive: wgpu::PrimitiveState { 
+            topology: inputs.topology, 
+            strip_index_format: inputs.strip_index_format, 
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+pub fn draw(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    view: &wgpu::TextureView,
+    render_pipeline: &wgpu::RenderPipeline,
+    num_vertices: u32
+) {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None }); 
+    { 
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { 
+            label: None, 
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment { 
+                view, 
+                resolve_target: None, 
+                ops: wgpu::Operations { 
+                    load: wgpu::LoadOp::Clear(wgpu::Color {r: 0.05, g:0.062, b:0.08, a:1.0}), 
+                    store: true, 
+                }, 
+            })], 
+            depth_stencil_attachment: None, 
+        }); 
+        rpass.set_pipeline(render_pipeline); 
+        rpass.draw(0..num_vertices, 0..1); 
+    } 
+    queue.submit(Some(encoder.finish())); 
+}
+
 pub async fn run(
     event_loop: EventLoop<()>,
     window: Window,
@@ -54,43 +122,10 @@ pub async fn run(
     surface.configure(&device, &config); 
 
 
-    // Load the shaders from disk
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: None,
-        source: inputs.source,
-    });
-    
-    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor { 
-        label: None,
-        bind_group_layouts: &[],
-        push_constant_ranges: &[], 
-    });
-
-    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: None,
-        layout: Some(&pipeline_layout),
-        vertex: wgpu::VertexState { 
-            module: &shader, 
-            entry_point: "vs_main", 
-            buffers: &[], 
-        },
-        fragment: Some(wgpu::FragmentState { 
-            module: &shader, 
-            entry_point: "fs_main", 
-            targets: &[Some(format.into())], 
-        }),
-        primitive: wgpu::PrimitiveState { 
-            topology: inputs.topology, 
-            strip_index_format: inputs.strip_index_format, 
-            ..Default::default()
-        },
-        depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
-        multiview: None,
-    });
+    let render_pipeline = create_pipeline(&device, format, inputs);
 
     event_loop.run(move |event, _, control_flow| {
-        let _ = (&instance, &adapter, &shader, &pipeline_layout); 
+        let _ = (&instance, &adapter); 
         *control_flow = ControlFlow::Wait; 
 
         match event {
@@ -106,24 +141,7 @@ pub async fn run(
             Event::RedrawRequested(_) => { 
                 let frame = surface.get_current_texture().unwrap(); 
                 let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default()); 
-                let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None }); 
-                { 
-                    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { 
-                        label: None, 
-                        color_attachments: &[Some(wgpu::RenderPassColorAttachment { 
-                            view: &view, 
-                            resolve_target: None, 
-                            ops: wgpu::Operations { 
-                                load: wgpu::LoadOp::Clear(wgpu::Color {r: 0.05, g:0.062, b:0.08, a:1.0}), 
-                                store: true, 
-                            }, 
-                        })], 
-                        depth_stencil_attachment: None, 
-                    }); 
-                    rpass.set_pipeline(&render_pipeline); 
-                    rpass.draw(0..num_vertices, 0..1); 
-                } 
-                queue.submit(Some(encoder.finish())); 
+                draw(&device, &queue, &view, &render_pipeline, num_vertices); 
                 frame.present(); 
             } 
             Event::WindowEvent { 
@@ -6,6 +6,7 @@ use winit::{
     window::{Window, WindowBuilder}, 
 }; 
 use bytemuck::{Pod, Zeroable, cast_slice};
+use hello_world::transforms;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -46,12 +47,8 @@ impl Vertex {
     }
 }
 
-struct State {
-    surface: wgpu::Surface,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
-    size: winit::dpi::PhysicalSize<u32>,
+pub struct State {
+    pub init: transforms::InitWgpu,
     pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -60,42 +57,12 @@ struct State {
 
 impl State {
     async fn new(window: &Window) -> Self {
-        let size = window.inner_size();
-        // let instance = wgpu::Instance::new(wgpu::Backends::DX12);
-        let instance = wgpu::Instance::new(wgpu::Backends::all());
-        let surface = unsafe {
-            instance.create_surface(window)
-        };
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions{
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor{
-                    label: Some("Default device"),
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::default(),
-                },
-                None)
-            .await
-            .unwrap();
-
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_supported_formats(&adapter)[0],
-            width: size.width,
-            height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: wgpu::CompositeAlphaMode::Auto,
-        };
-        surface.configure(&device, &config);
+        let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(init)
+    }
 
+    pub fn with_init(init: transforms::InitWgpu) -> Self {
+        let device = &init.device;
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("triangle.wgsl").into()),
@@ -119,7 +86,7 @@ impl State {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: init.config.format,
                     blend: Some(wgpu::BlendState {
                         color: wgpu::BlendComponent::REPLACE,
                         alpha: wgpu::BlendComponent::REPLACE,
@@ -152,11 +119,7 @@ impl State {
         let indices_len = INDICES.len() as u32;
 
         Self {
-            surface,
-            device,
-            queue,
-            config,
-            size,
+            init,
             pipeline,
             vertex_buffer,
             index_buffer,
@@ -166,10 +129,7 @@ impl State {
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 { 
-            self.size = new_size; 
-            self.config.width = new_size.width; 
-            self.config.height = new_size.height; 
-            self.surface.configure(&self.device, &self.config); 
+            self.init.resize(new_size); 
         }
     }
 
@@ -180,11 +140,11 @@ impl State {
 
     fn update(&mut self) {}
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.init.get_current_frame()?;
+        let view = output.texture().create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        let mut encoder = self.init.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
 
@@ -213,7 +173,7 @@ impl State {
             render_pass.draw_indexed(0..self.indices_len, 0, 0..1);
         }
 
-        self.queue.submit(iter::once(encoder.finish()));
+        self.init.queue.submit(iter::once(encoder.finish()));
         output.present();
         Ok(())
     }
@@ -254,7 +214,7 @@ fn main() {
                 state.update();
                 match state.render() {
                     Ok(_) => {}
-                    Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
+                    Err(wgpu::SurfaceError::Lost) => state.resize(state.init.size),
                     Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
                     Err(e) => eprintln!("{:?}", e),
                 }
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
+use anyhow::Context;
 use bytemuck::{cast_slice, Pod, Zeroable};
-use cgmath::{Matrix, Matrix4, SquareMatrix};
+use cgmath::{Matrix, SquareMatrix};
 use std::{iter, mem};
 use wgpu::util::DeviceExt;
 use winit::{
@@ -8,15 +9,64 @@ use winit::{
     event_loop::{ControlFlow, EventLoop},
     window::Window,
 };
-#[path = "../../common/surface_data.rs"]
-mod surface;
-#[path = "../../common/texture_data.rs"]
-mod texture;
-#[path = "../../common/transforms.rs"]
-pub mod transforms;
+use hello_world::colormap::Colormap;
+use hello_world::mesh::{Mesh, NormalMode};
+use hello_world::{camera, capture, gltf, obj, procedural, surface_data, texture_data as texture};
+use hello_world::{transforms, vertex_data};
 
 const ANIMATION_SPEED: f32 = 1.0;
 const IS_PERSPECTIVE: bool = true;
+// near depth at 1 and far depth at 0 in a float depth buffer, see transforms::Projection
+const REVERSE_Z: bool = false;
+
+// the directory of the texture and model files of the examples
+const ASSETS: &str = "examples/ch10/assets/";
+
+// the size of the procedural textures, in texels along each side
+const PROCEDURAL_SIZE: u32 = 1024;
+
+// the image on the object: a procedural texture, which needs no assets, or an image file
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageSource {
+    Procedural(procedural::Procedural),
+    File(String),
+}
+
+impl ImageSource {
+    // a procedural texture for names such as "uv" or "noise:8", otherwise the file name in dir
+    pub fn from_name(name: &str, dir: &str) -> anyhow::Result<Self> {
+        if procedural::is_procedural_name(name) {
+            Ok(Self::Procedural(procedural::Procedural::from_name(name)?))
+        } else {
+            Ok(Self::File([dir, name].join("")))
+        }
+    }
+
+    // the image itself, generated or read from the file; the texture made from it on the gpu
+    // does not keep it
+    pub fn image(&self) -> anyhow::Result<image::RgbaImage> {
+        match self {
+            Self::Procedural(procedural) => Ok(procedural.image(PROCEDURAL_SIZE, PROCEDURAL_SIZE)),
+            Self::File(img_file) => Ok(image::open(img_file)
+                .with_context(|| format!("failed to open image '{}'", img_file))?
+                .to_rgba8()),
+        }
+    }
+}
+
+// save the mesh as a binary gltf file, with the light's material and the image as its base color
+// texture sampled with the given u/v address modes
+pub fn export_glb(
+    mesh: &Mesh,
+    light: &Light,
+    image: &ImageSource,
+    address_modes: [wgpu::AddressMode; 2],
+    file_name: &str,
+) -> anyhow::Result<()> {
+    let image = image.image()?;
+    let texture = gltf::BaseColorTexture { image: &image, address_modes };
+    gltf::save_glb(mesh, file_name, &light.material(), Some(texture))
+}
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -47,6 +97,20 @@ pub fn light(
     }
 }
 
+impl Light {
+    // the pbr material closest to this light's blinn-phong parameters, for gltf export
+    pub fn material(&self) -> gltf::Material {
+        let sc = self.specular_color;
+        gltf::Material::from_blinn_phong(
+            self.diffuse_intensity,
+            self.specular_intensity,
+            [sc[0], sc[1], sc[2]],
+            self.specular_shininess,
+            self.is_two_side != 0,
+        )
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct Vertex {
@@ -63,6 +127,57 @@ pub fn vertex(p: [f32; 3], n: [f32; 3], t: [f32; 2]) -> Vertex {
     }
 }
 
+// the vertices of a mesh for the textured pipeline
+pub fn vertices(mesh: &Mesh) -> Vec<Vertex> {
+    mesh.interleave(|p, n, _c, t| vertex(p, n, t))
+}
+
+// the meshes of the textured examples, kept here so that the golden tests render the same scenes
+pub fn cube_mesh() -> Mesh {
+    vertex_data::cube_mesh()
+}
+
+pub fn sphere_mesh() -> Mesh {
+    vertex_data::sphere_data(1.5, 15, 20)
+}
+
+pub fn torus_mesh() -> Mesh {
+    vertex_data::torus_data(1.5, 0.4, 40, 20, NormalMode::Analytic)
+}
+
+// y = f(x, z) over [-8, 8] x [-8, 8]
+pub fn surface_mesh(f: &dyn Fn(f32, f32) -> [f32; 3]) -> Mesh {
+    surface_data::simple_surface_data(
+        f, &Colormap::default(), -8.0, 8.0, -8.0, 8.0, 30, 30, 1.5, 0.3, NormalMode::AreaWeighted,
+    )
+}
+
+// center the model and scale it so that its largest extent spans [-1, 1], like the cube
+pub fn fit_to_view(mesh: &mut Mesh) {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in &mesh.positions {
+        for k in 0..3 {
+            min[k] = min[k].min(p[k]);
+            max[k] = max[k].max(p[k]);
+        }
+    }
+    let extent = (0..3).map(|k| max[k] - min[k]).fold(0.0, f32::max);
+    let scale = if extent > 0.0 { 2.0 / extent } else { 1.0 };
+    for p in &mut mesh.positions {
+        for k in 0..3 {
+            p[k] = (p[k] - 0.5 * (min[k] + max[k])) * scale;
+        }
+    }
+}
+
+// an obj model fitted to the view, unindexed for the non-indexed pipeline
+pub fn model_mesh(model_file: &str) -> anyhow::Result<Mesh> {
+    let mut mesh = obj::load_obj(model_file)?;
+    fit_to_view(&mut mesh);
+    Ok(mesh.unindexed())
+}
+
 impl Vertex {
     const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
         wgpu::vertex_attr_array![0=>Float32x4, 1=>Float32x4, 2=>Float32x2];
@@ -82,9 +197,12 @@ pub struct State {
     vertex_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
     vertex_uniform_buffer: wgpu::Buffer,
-    view_mat: Matrix4<f32>,
-    project_mat: Matrix4<f32>,
+    fragment_uniform_buffer: wgpu::Buffer,
+    pub camera: camera::Camera,
+    pub projection: transforms::Projection,
     num_vertices: u32,
+    // the time passed to the previous update, to move the fly camera by the frame time
+    last_update: std::time::Duration,
 
     image_texture: texture::Texture,
     texture_bind_group: wgpu::BindGroup,
@@ -95,21 +213,63 @@ impl State {
         window: &Window,
         vertex_data: &Vec<Vertex>,
         light_data: Light,
-        img_file: &str,
+        image: &ImageSource,
         u_mode: wgpu::AddressMode,
         v_mode: wgpu::AddressMode,
-    ) -> Self {
+    ) -> anyhow::Result<Self> {
         let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(init, vertex_data, light_data, image, u_mode, v_mode)
+    }
 
-        // create image texture
-        let image_texture = texture::Texture::create_texture_data(
-            &init.device,
-            &init.queue,
-            img_file,
-            u_mode,
-            v_mode,
+    pub async fn new_headless(
+        width: u32,
+        height: u32,
+        vertex_data: &Vec<Vertex>,
+        light_data: Light,
+        image: &ImageSource,
+        u_mode: wgpu::AddressMode,
+        v_mode: wgpu::AddressMode,
+    ) -> anyhow::Result<Self> {
+        let init = transforms::InitWgpu::init_headless(
+            width,
+            height,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
         )
-        .unwrap();
+        .await;
+        Self::with_init(init, vertex_data, light_data, image, u_mode, v_mode)
+    }
+
+    pub fn with_init(
+        init: transforms::InitWgpu,
+        vertex_data: &Vec<Vertex>,
+        light_data: Light,
+        image: &ImageSource,
+        u_mode: wgpu::AddressMode,
+        v_mode: wgpu::AddressMode,
+    ) -> anyhow::Result<Self> {
+        // create image texture, mipmapped and anisotropically filtered so that it does not shimmer
+        // as the object turns away
+        let texture_options = texture::TextureOptions {
+            anisotropy: 16,
+            ..texture::TextureOptions::new(u_mode, v_mode)
+        };
+        let image_texture = match image {
+            ImageSource::Procedural(procedural) => texture::Texture::create_procedural_texture(
+                &init.device,
+                &init.queue,
+                &init.mipmaps,
+                procedural,
+                PROCEDURAL_SIZE,
+                &texture_options,
+            ),
+            ImageSource::File(img_file) => texture::Texture::create_texture_with_options(
+                &init.device,
+                &init.queue,
+                &init.mipmaps,
+                img_file,
+                &texture_options,
+            )?,
+        };
         let texture_bind_group_layout =
             init.device
                 .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -157,17 +317,17 @@ impl State {
             });
 
         // uniform data
+        // the view matrix comes from the camera, which starts at camera_position in orbit mode;
+        // tab switches to the wasd fly camera
         let camera_position = (2.5, 1.25, 2.5).into();
         let look_direction = (0.0, 0.0, 0.0).into();
-        let up_direction = cgmath::Vector3::unit_y();
+        let camera = camera::Camera::new(camera_position, look_direction);
 
-        let (view_mat, project_mat, _view_project_mat) = transforms::create_view_projection(
-            camera_position,
-            look_direction,
-            up_direction,
+        let mut projection = transforms::Projection::new(
             init.config.width as f32 / init.config.height as f32,
             IS_PERSPECTIVE,
         );
+        projection.reverse_z = REVERSE_Z;
 
         // create vertex uniform buffer
         // model_mat and view_projection_mat will be stored in vertex_uniform_buffer inside
@@ -180,7 +340,8 @@ impl State {
         });
 
         // create fragment uniform buffer. here we set eye_position = camera_position and
-        // light_position = eye_position
+        // light_position = eye_position; both follow the camera and are stored inside the
+        // update function
         let fragment_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Fragment Uniform Buffer"),
             size: 32,
@@ -188,20 +349,6 @@ impl State {
             mapped_at_creation: false,
         });
 
-        // store light and eye positions
-        let light_position: &[f32; 3] = camera_position.as_ref();
-        let eye_position: &[f32; 3] = camera_position.as_ref();
-        init.queue.write_buffer(
-            &fragment_uniform_buffer,
-            0,
-            bytemuck::cast_slice(light_position),
-        );
-        init.queue.write_buffer(
-            &fragment_uniform_buffer,
-            16,
-            bytemuck::cast_slice(eye_position),
-        );
-
         // create light uniform buffer
         let light_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Light Uniform Buffer"),
@@ -310,9 +457,9 @@ impl State {
                 },
                 //depth_stencil: None,
                 depth_stencil: Some(wgpu::DepthStencilState {
-                    format: wgpu::TextureFormat::Depth24Plus,
+                    format: projection.depth_format(),
                     depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    depth_compare: projection.depth_compare(),
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
                 }),
@@ -330,42 +477,39 @@ impl State {
 
         let num_vertices = vertex_data.len() as u32;
 
-        Self {
+        Ok(Self {
             init,
             pipeline,
             vertex_buffer,
             uniform_bind_group,
             vertex_uniform_buffer,
-            view_mat,
-            project_mat,
+            fragment_uniform_buffer,
+            camera,
+            projection,
             num_vertices,
+            last_update: std::time::Duration::ZERO,
 
             image_texture,
             texture_bind_group,
-        }
+        })
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.init.size = new_size;
-            self.init.config.width = new_size.width;
-            self.init.config.height = new_size.height;
-            self.init
-                .surface
-                .configure(&self.init.device, &self.init.config);
-            self.project_mat = transforms::create_projection(
-                new_size.width as f32 / new_size.height as f32,
-                IS_PERSPECTIVE,
-            );
+            self.init.resize(new_size);
+            self.projection.resize(new_size.width, new_size.height);
         }
     }
 
-    #[allow(unused_variables)]
     pub fn input(&mut self, event: &WindowEvent) -> bool {
-        false
+        self.camera.process_event(event)
     }
 
     pub fn update(&mut self, dt: std::time::Duration) {
+        // dt is the time since rendering started; the camera moves by the time since last frame
+        self.camera.update(dt.saturating_sub(self.last_update).as_secs_f32());
+        self.last_update = dt;
+
         // update uniform buffer
         let dt = ANIMATION_SPEED * dt.as_secs_f32();
         let model_mat = transforms::create_transforms(
@@ -373,7 +517,7 @@ impl State {
             [dt.sin(), dt.cos(), 0.0],
             [1.0, 1.0, 1.0],
         );
-        let view_project_mat = self.project_mat * self.view_mat;
+        let view_project_mat = self.projection.matrix() * self.camera.view_matrix();
 
         let normal_mat = (model_mat.invert().unwrap()).transpose();
 
@@ -396,12 +540,27 @@ impl State {
             128,
             bytemuck::cast_slice(normal_ref),
         );
+
+        // store light and eye positions
+        let eye = self.camera.eye();
+        let light_position: &[f32; 3] = eye.as_ref();
+        let eye_position: &[f32; 3] = eye.as_ref();
+        self.init.queue.write_buffer(
+            &self.fragment_uniform_buffer,
+            0,
+            bytemuck::cast_slice(light_position),
+        );
+        self.init.queue.write_buffer(
+            &self.fragment_uniform_buffer,
+            16,
+            bytemuck::cast_slice(eye_position),
+        );
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.init.surface.get_current_texture()?;
+        let output = self.init.get_current_frame()?;
         let view = output
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
 
         let depth_texture = self.init.device.create_texture(&wgpu::TextureDescriptor {
@@ -413,7 +572,7 @@ impl State {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
+            format: self.projection.depth_format(),
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             label: None,
         });
@@ -446,7 +605,7 @@ impl State {
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: &depth_view,
                     depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
+                        load: wgpu::LoadOp::Clear(self.projection.depth_clear()),
                         store: false,
                     }),
                     stencil_ops: None,
@@ -461,36 +620,44 @@ impl State {
         }
 
         self.init.queue.submit(iter::once(encoder.finish()));
-        output.present();
+        if let Err(e) = self.init.finish_frame(output) {
+            eprintln!("{:?}", e);
+        }
 
         Ok(())
     }
 }
 
 pub fn run(
-    vertex_data: &Vec<Vertex>,
+    mesh: &Mesh,
     light_data: Light,
     file_name: &str,
     u_mode: wgpu::AddressMode,
     v_mode: wgpu::AddressMode,
     title: &str,
 ) {
-    let path = "examples/ch10/assets/";
-    let img_file = [path, file_name].join("");
     env_logger::init();
     let event_loop = EventLoop::new();
     let window = winit::window::WindowBuilder::new()
         .build(&event_loop)
         .unwrap();
     window.set_title(&*format!("ch10_{}: {}", title, file_name));
-    let mut state = pollster::block_on(State::new(
-        &window,
-        &vertex_data,
-        light_data,
-        &img_file,
-        u_mode,
-        v_mode,
-    ));
+    let vertex_data = vertices(mesh);
+    let state = ImageSource::from_name(file_name, ASSETS).and_then(|image| {
+        let state = State::new(&window, &vertex_data, light_data, &image, u_mode, v_mode);
+        let state = pollster::block_on(state)?;
+        Ok((image, state))
+    });
+    let (image, mut state) = match state {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    };
+    let capture_file = format!("ch10_{}.png", title);
+    let export_file = format!("ch10_{}.glb", title);
+    let mesh = mesh.clone();
     let render_start_time = std::time::Instant::now();
     event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent {
@@ -509,6 +676,24 @@ pub fn run(
                             },
                         ..
                     } => *control_flow = ControlFlow::Exit,
+                    _ if capture::is_capture_key(event) => {
+                        state.init.request_capture(&capture_file);
+                    }
+                    // F11 saves the object with its texture and material as a gltf file
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::F11),
+                                ..
+                            },
+                        ..
+                    } => {
+                        let (modes, file) = ([u_mode, v_mode], &export_file);
+                        if let Err(e) = export_glb(&mesh, &light_data, &image, modes, file) {
+                            eprintln!("{:?}", e);
+                        }
+                    }
                     WindowEvent::Resized(physical_size) => {
                         state.resize(*physical_size);
                     }
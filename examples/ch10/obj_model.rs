@@ -0,0 +1,22 @@
+pub mod common;
+
+fn main() {
+    // usage: ch10_obj_model [model.obj] [texture file in examples/ch10/assets/, or a procedural
+    // texture such as uv, checker:8, noise:4 or gradient:viridis]
+    let args: Vec<String> = std::env::args().collect();
+    let model_file = args.get(1).map_or("examples/ch10/assets/cube.obj", |s| s.as_str());
+    let file_name = args.get(2).map_or("bricks.png", |s| s.as_str());
+
+    let mesh = match common::model_mesh(model_file) {
+        Ok(mesh) => mesh,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    };
+    let light_data = common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let u_mode = wgpu::AddressMode::Repeat;
+    let v_mode = wgpu::AddressMode::Repeat;
+
+    common::run(&mesh, light_data, file_name, u_mode, v_mode, "obj_model");
+}
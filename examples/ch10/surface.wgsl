@@ -0,0 +1,163 @@
+// vertex shader
+
+struct Uniforms {
+    model_mat : mat4x4<f32>,
+    view_project_mat : mat4x4<f32>,
+    normal_mat : mat4x4<f32>,
+};
+
+@binding(0) @group(0) var<uniform> uniforms: Uniforms;
+
+struct Input {
+    @location(0) pos    : vec4<f32>,
+    @location(1) normal : vec4<f32>,
+    @location(2) value  : f32,
+};
+
+struct Output {
+    @builtin(position) position     : vec4<f32>,
+    @location(0)       v2f_position : vec4<f32>,
+    @location(1)       v2f_normal   : vec4<f32>,
+    @location(2)       v2f_value    : f32,
+};
+
+@vertex
+fn vs_main(in: Input) -> Output {
+    var output: Output;
+    let m_position: vec4<f32> = uniforms.model_mat * in.pos;
+    output.position = uniforms.view_project_mat * m_position;
+    output.v2f_position = m_position;
+    output.v2f_normal = uniforms.normal_mat * in.normal;
+    output.v2f_value = in.value;
+    return output;
+}
+
+// fragment shader
+
+struct Uniforms {
+    light_position : vec4<f32>,
+    eye_position : vec4<f32>,
+};
+
+@binding(1) @group(0) var<uniform> frag_uniforms : Uniforms;
+
+struct Uniforms {
+    specular_color : vec4<f32>,
+    ambient_intensity: f32,
+    diffuse_intensity :f32,
+    specular_intensity: f32,
+    specular_shininess: f32,
+    is_two_side: i32,
+    _pad0: f32,
+    _pad1: f32,
+    _pad2: f32,
+};
+
+@binding(2) @group(0) var<uniform> light_uniforms : Uniforms;
+
+// the value range mapped onto the colormap, and the number of bands it is split into (0 for a
+// smooth gradient)
+struct Uniforms {
+    value_min : f32,
+    value_max : f32,
+    bands : f32,
+    _pad0 : f32,
+};
+
+@binding(3) @group(0) var<uniform> colormap_uniforms : Uniforms;
+@binding(4) @group(0) var colormap_texture : texture_2d<f32>;
+@binding(5) @group(0) var colormap_sampler : sampler;
+
+// the color at t in [0, 1] along the colormap
+fn colormap_at(t_in: f32) -> vec3<f32> {
+    var t: f32 = t_in;
+    // each band takes the color at its own position, as colormap::Colormap::discrete does
+    let bands: f32 = colormap_uniforms.bands;
+    if(bands >= 2.0) {
+        t = min(floor(t * bands), bands - 1.0) / (bands - 1.0);
+    } else if(bands >= 1.0) {
+        t = 0.5;
+    }
+    // the texel centers of the two ends are at half a texel from the edges
+    let width: f32 = f32(textureDimensions(colormap_texture).x);
+    let u: f32 = (t * (width - 1.0) + 0.5) / width;
+    return textureSample(colormap_texture, colormap_sampler, vec2<f32>(u, 0.5)).rgb;
+}
+
+// the color of a value within the value range; a flat range gives the start of the colormap,
+// as colormap::Colormap::color_lerp does
+fn colormap(value: f32) -> vec3<f32> {
+    let range: f32 = colormap_uniforms.value_max - colormap_uniforms.value_min;
+    var t: f32 = 0.0;
+    if(range > 0.0) {
+        t = clamp((value - colormap_uniforms.value_min) / range, 0.0, 1.0);
+    }
+    return colormap_at(t);
+}
+
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    let N: vec3<f32> = normalize(in.v2f_normal.xyz);
+    let L: vec3<f32> = normalize(frag_uniforms.light_position.xyz - in.v2f_position.xyz);
+    let V: vec3<f32> = normalize(frag_uniforms.eye_position.xyz - in.v2f_position.xyz);
+    let H: vec3<f32> = normalize(L + V);
+
+    // front side
+    var diffuse: f32 = light_uniforms.diffuse_intensity * max(dot(N, L), 0.0);
+    var specular: f32 = light_uniforms.specular_intensity *
+        pow(max(dot(N, H), 0.0), light_uniforms.specular_shininess);
+
+    // back side
+    var is_two_side:i32 = light_uniforms.is_two_side;
+    if(is_two_side == 1) {
+        diffuse = diffuse + light_uniforms.diffuse_intensity * max(dot(-N, L), 0.0);
+        specular = specular + light_uniforms.specular_intensity *
+            pow(max(dot(-N, H),0.0), light_uniforms.specular_shininess);
+    }
+
+    // the colormap color takes the place of the texture color
+    let ambient: f32 = light_uniforms.ambient_intensity;
+    let final_color: vec3<f32> = colormap(in.v2f_value) * (ambient + diffuse)
+        + light_uniforms.specular_color.rgb * specular;
+
+    return vec4<f32>(final_color, 1.0);
+}
+
+// the wireframe overlay is drawn unlit in a single color
+@fragment
+fn fs_wire(in: Output) -> @location(0) vec4<f32> {
+    return vec4<f32>(0.1, 0.1, 0.1, 1.0);
+}
+
+// the colorbar overlay, in screen space over the scene
+
+struct ColorbarInput {
+    @location(0) pos   : vec2<f32>,
+    @location(1) t     : f32,
+    @location(2) solid : f32,
+};
+
+struct ColorbarOutput {
+    @builtin(position) position : vec4<f32>,
+    @location(0)       t        : f32,
+    @location(1)       solid    : f32,
+};
+
+@vertex
+fn vs_colorbar(in: ColorbarInput) -> ColorbarOutput {
+    var output: ColorbarOutput;
+    output.position = vec4<f32>(in.pos, 0.0, 1.0);
+    output.t = in.t;
+    output.solid = in.solid;
+    return output;
+}
+
+@fragment
+fn fs_colorbar(in: ColorbarOutput) -> @location(0) vec4<f32> {
+    // the strip goes through the same lookup and bands as the surface
+    let color: vec3<f32> = colormap_at(in.t);
+    if(in.solid > 0.5) {
+        return vec4<f32>(0.9, 0.9, 0.9, 1.0);
+    }
+    return vec4<f32>(color, 1.0);
+}
@@ -0,0 +1,706 @@
+pub mod common;
+use bytemuck::{cast_slice, Pod, Zeroable};
+use cgmath::{Matrix4, SquareMatrix};
+use std::{iter, mem};
+use wgpu::util::DeviceExt;
+use winit::{
+    event::*,
+    event_loop::{ControlFlow, EventLoop},
+    window::Window,
+};
+use hello_world::colorbar::{Colorbar, ColorbarPosition, ColorbarVertex};
+use hello_world::colormap::{self, Colormap};
+use hello_world::mesh::{Mesh, NormalMode};
+use hello_world::{camera, capture, surfaces, texture_data as texture, transforms};
+
+const IS_PERSPECTIVE: bool = true;
+// near depth at 1 and far depth at 0 in a float depth buffer, see transforms::Projection
+const REVERSE_Z: bool = false;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct Vertex {
+    pub position: [f32; 4],
+    pub normal: [f32; 4],
+    // the scalar the shader looks up in the colormap texture
+    pub value: f32,
+}
+
+pub fn vertex(p: [f32; 3], n: [f32; 3], value: f32) -> Vertex {
+    Vertex {
+        position: [p[0], p[1], p[2], 1.0],
+        normal: [n[0], n[1], n[2], 1.0],
+        value,
+    }
+}
+
+impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0=>Float32x4, 1=>Float32x4, 2=>Float32];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// the smooth-shaded, indexed mesh of a built-in surface. the colors are not used: the shader
+// colors the surface by height through a colormap texture.
+pub fn create_mesh(surface_name: &str) -> anyhow::Result<Mesh> {
+    let surface = surfaces::find(surface_name)?;
+    let colormap = Colormap::from_name(surface.colormap)?;
+    Ok(surface.mesh_index(&colormap, NormalMode::AreaWeighted))
+}
+
+// the built-in colormaps the c key steps through
+fn next_colormap(name: &str) -> &'static str {
+    let names = colormap::names();
+    let i = names.iter().position(|n| *n == name).map_or(0, |i| i + 1);
+    names[i % names.len()]
+}
+
+pub struct State {
+    pub init: transforms::InitWgpu,
+    pipeline: wgpu::RenderPipeline,
+    wire_pipeline: wgpu::RenderPipeline,
+    colorbar_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_format: wgpu::IndexFormat,
+    num_indices: u32,
+    line_buffer: wgpu::Buffer,
+    num_line_indices: u32,
+    uniform_bind_group: wgpu::BindGroup,
+    vertex_uniform_buffer: wgpu::Buffer,
+    fragment_uniform_buffer: wgpu::Buffer,
+    colormap_uniform_buffer: wgpu::Buffer,
+    colormap_texture: texture::Texture,
+    pub projection: transforms::Projection,
+
+    // the colormap is applied on the gpu, so all of these can change without touching the mesh
+    pub colormap_name: String,
+    // the values mapped onto the ends of the colormap, and the range of the mesh itself
+    pub value_range: [f32; 2],
+    data_range: [f32; 2],
+    // number of flat bands, 0 for a smooth gradient
+    pub bands: u32,
+
+    pub camera: camera::Camera,
+    // the time passed to the previous update, to move the fly camera by the frame time
+    last_update: std::time::Duration,
+    pub show_wireframe: bool,
+
+    // the legend of the colormap, drawn over the scene
+    pub colorbar: Option<Colorbar>,
+    // the colorbar triangles, rebuilt when the colorbar, value range or window size change
+    colorbar_buffer: Option<wgpu::Buffer>,
+    num_colorbar_vertices: u32,
+    colorbar_layout: Option<(Colorbar, [f32; 2], [u32; 2])>,
+}
+
+impl State {
+    pub async fn new(
+        window: &Window,
+        mesh: &Mesh,
+        light_data: common::Light,
+        colormap_name: &str,
+    ) -> anyhow::Result<Self> {
+        let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(init, mesh, light_data, colormap_name)
+    }
+
+    pub fn with_init(
+        init: transforms::InitWgpu,
+        mesh: &Mesh,
+        light_data: common::Light,
+        colormap_name: &str,
+    ) -> anyhow::Result<Self> {
+        // report a misspelled colormap instead of drawing in the jet fallback
+        let colormap = Colormap::from_name(colormap_name)?;
+        let shader = init
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("surface.wgsl").into()),
+            });
+
+        // model_mat, view_projection_mat and normal_mat are written in the update function
+        let vertex_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Vertex Uniform Buffer"),
+            size: 192,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // light and eye positions, both following the camera
+        let fragment_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Fragment Uniform Buffer"),
+            size: 32,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let light_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Light Uniform Buffer"),
+            size: 48, // used 36, with another 3 float for padding
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        init.queue.write_buffer(&light_uniform_buffer, 0, cast_slice(&[light_data]));
+
+        // value range and bands, written in the update function
+        let colormap_uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Colormap Uniform Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let colormap_texture =
+            texture::Texture::create_colormap_texture(&init.device, &init.queue, &colormap);
+
+        let uniform_entry = |binding, visibility| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let uniform_bind_group_layout =
+            init.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    entries: &[
+                        uniform_entry(0, wgpu::ShaderStages::VERTEX),
+                        uniform_entry(1, wgpu::ShaderStages::FRAGMENT),
+                        uniform_entry(2, wgpu::ShaderStages::FRAGMENT),
+                        uniform_entry(3, wgpu::ShaderStages::FRAGMENT),
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 4,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                multisampled: false,
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 5,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                    label: Some("Uniform Bind Group Layout"),
+                });
+
+        let uniform_bind_group = init.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &uniform_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: vertex_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: fragment_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: light_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: colormap_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&colormap_texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::Sampler(&colormap_texture.sampler),
+                },
+            ],
+            label: Some("Uniform Bind Group"),
+        });
+
+        let mut projection = transforms::Projection::new(
+            init.config.width as f32 / init.config.height as f32,
+            IS_PERSPECTIVE,
+        );
+        projection.reverse_z = REVERSE_Z;
+
+        let pipeline_layout = init
+            .device
+            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[&uniform_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        // the surface is pushed back a little in depth (towards 0 with reverse z) so that the
+        // wireframe drawn over it is not hidden by z-fighting
+        let create_pipeline = |label, entry_point, topology, bias| {
+            init.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: "vs_main",
+                        buffers: &[Vertex::desc()],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point,
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: init.config.format,
+                            blend: Some(wgpu::BlendState {
+                                color: wgpu::BlendComponent::REPLACE,
+                                alpha: wgpu::BlendComponent::REPLACE,
+                            }),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology,
+                        ..Default::default()
+                    },
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: projection.depth_format(),
+                        depth_write_enabled: true,
+                        depth_compare: projection.depth_compare(),
+                        stencil: wgpu::StencilState::default(),
+                        bias,
+                    }),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                })
+        };
+        let pipeline = create_pipeline(
+            "Render Pipeline",
+            "fs_main",
+            wgpu::PrimitiveTopology::TriangleList,
+            wgpu::DepthBiasState {
+                constant: if REVERSE_Z { -2 } else { 2 },
+                slope_scale: if REVERSE_Z { -1.0 } else { 1.0 },
+                clamp: 0.0,
+            },
+        );
+        let wire_pipeline = create_pipeline(
+            "Wireframe Pipeline",
+            "fs_wire",
+            wgpu::PrimitiveTopology::LineList,
+            wgpu::DepthBiasState::default(),
+        );
+        // the colorbar is drawn last, over everything
+        let colorbar_pipeline =
+            init.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Colorbar Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: "vs_colorbar",
+                        buffers: &[ColorbarVertex::desc()],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point: "fs_colorbar",
+                        targets: &[Some(init.config.format.into())],
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: Some(wgpu::DepthStencilState {
+                        format: projection.depth_format(),
+                        depth_write_enabled: false,
+                        depth_compare: wgpu::CompareFunction::Always,
+                        stencil: wgpu::StencilState::default(),
+                        bias: wgpu::DepthBiasState::default(),
+                    }),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                });
+
+        // the value looked up in the colormap is the height, as with the baked vertex colors
+        let vertex_data = mesh.interleave(|p, n, _c, _t| vertex(p, n, p[1]));
+        let data_range = mesh.positions.iter().fold([f32::MAX, f32::MIN], |[lo, hi], p| {
+            [lo.min(p[1]), hi.max(p[1])]
+        });
+        let vertex_buffer = init
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Vertex Buffer"),
+                contents: cast_slice(&vertex_data),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+
+        // unindexed meshes are drawn through an index buffer as well
+        let mut mesh = mesh.clone();
+        if mesh.indices.is_none() {
+            mesh.indices = Some((0..mesh.num_vertices() as u32).collect());
+        }
+        let index_data = mesh.index_data().unwrap();
+        let index_buffer = init
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Index Buffer"),
+                contents: index_data.contents(),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+        let line_indices: Vec<u32> = mesh.edges().concat();
+        let line_buffer = init
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Line Index Buffer"),
+                contents: cast_slice(&line_indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+
+        Ok(Self {
+            init,
+            pipeline,
+            wire_pipeline,
+            colorbar_pipeline,
+            vertex_buffer,
+            index_buffer,
+            index_format: index_data.format(),
+            num_indices: index_data.len() as u32,
+            line_buffer,
+            num_line_indices: line_indices.len() as u32,
+            uniform_bind_group,
+            vertex_uniform_buffer,
+            fragment_uniform_buffer,
+            colormap_uniform_buffer,
+            colormap_texture,
+            projection,
+            colormap_name: colormap_name.to_string(),
+            value_range: data_range,
+            data_range,
+            bands: 0,
+
+            // the ch10 camera position; tab switches to the wasd fly camera
+            camera: camera::Camera::new((2.5, 1.25, 2.5).into(), (0.0, 0.0, 0.0).into()),
+            last_update: std::time::Duration::ZERO,
+            show_wireframe: false,
+
+            colorbar: None,
+            colorbar_buffer: None,
+            num_colorbar_vertices: 0,
+            colorbar_layout: None,
+        })
+    }
+
+    // switch to another colormap by name; only the colormap texture is rewritten
+    pub fn set_colormap(&mut self, colormap_name: &str) -> anyhow::Result<()> {
+        let colormap = Colormap::from_name(colormap_name)?;
+        self.colormap_texture.write_colormap(&self.init.queue, &colormap);
+        self.colormap_name = colormap_name.to_string();
+        Ok(())
+    }
+
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        if new_size.width > 0 && new_size.height > 0 {
+            self.init.resize(new_size);
+            self.projection.resize(new_size.width, new_size.height);
+        }
+    }
+
+    // the mouse and keyboard move the camera, and
+    //   space          toggles the wireframe
+    //   c              steps through the colormaps, whose name is shown in the window title
+    //   b              steps through 0 (smooth), 5, 10 and 20 bands
+    //   , / .          shift the value range down / up
+    //   - / =          widen / narrow the value range
+    //   0              back to the value range of the surface
+    //   l              moves the colorbar around the window edges, then hides it
+    pub fn input(&mut self, event: &WindowEvent) -> bool {
+        let key = match event {
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => *key,
+            _ => return self.camera.process_event(event),
+        };
+        let [lo, hi] = self.value_range;
+        let step = 0.05 * (self.data_range[1] - self.data_range[0]);
+        match key {
+            VirtualKeyCode::Space => self.show_wireframe = !self.show_wireframe,
+            VirtualKeyCode::C => {
+                let name = next_colormap(&self.colormap_name);
+                self.set_colormap(name).expect("built-in colormap names are always known");
+            }
+            VirtualKeyCode::B => {
+                self.bands = match self.bands {
+                    0 => 5,
+                    5 => 10,
+                    10 => 20,
+                    _ => 0,
+                }
+            }
+            VirtualKeyCode::Comma => self.value_range = [lo - step, hi - step],
+            VirtualKeyCode::Period => self.value_range = [lo + step, hi + step],
+            VirtualKeyCode::Minus => self.value_range = [lo - step, hi + step],
+            VirtualKeyCode::Equals if hi - lo > 3.0 * step => {
+                self.value_range = [lo + step, hi - step]
+            }
+            VirtualKeyCode::Key0 => self.value_range = self.data_range,
+            VirtualKeyCode::L => {
+                let position = match self.colorbar.map(|c| c.position) {
+                    None => Some(ColorbarPosition::Right),
+                    Some(ColorbarPosition::Right) => Some(ColorbarPosition::Bottom),
+                    Some(ColorbarPosition::Bottom) => Some(ColorbarPosition::Left),
+                    Some(ColorbarPosition::Left) => Some(ColorbarPosition::Top),
+                    Some(ColorbarPosition::Top) => None,
+                };
+                self.colorbar = position.map(|position| Colorbar {
+                    position,
+                    ..self.colorbar.unwrap_or(Colorbar::new(position))
+                });
+            }
+            _ => return self.camera.process_event(event),
+        }
+        true
+    }
+
+    // dt is the time since rendering started
+    pub fn update(&mut self, dt: std::time::Duration) {
+        self.camera.update(dt.saturating_sub(self.last_update).as_secs_f32());
+        self.last_update = dt;
+
+        let eye = self.camera.eye();
+        let view_project_mat = self.projection.matrix() * self.camera.view_matrix();
+        let model_mat = Matrix4::<f32>::identity();
+
+        let model_ref: &[f32; 16] = model_mat.as_ref();
+        let view_projection_ref: &[f32; 16] = view_project_mat.as_ref();
+        let queue = &self.init.queue;
+        queue.write_buffer(&self.vertex_uniform_buffer, 0, cast_slice(model_ref));
+        queue.write_buffer(&self.vertex_uniform_buffer, 64, cast_slice(view_projection_ref));
+        // the normal matrix of the identity model matrix is the identity as well
+        queue.write_buffer(&self.vertex_uniform_buffer, 128, cast_slice(model_ref));
+
+        // the light sits at the eye
+        let eye_ref: &[f32; 3] = eye.as_ref();
+        queue.write_buffer(&self.fragment_uniform_buffer, 0, cast_slice(eye_ref));
+        queue.write_buffer(&self.fragment_uniform_buffer, 16, cast_slice(eye_ref));
+
+        let [value_min, value_max] = self.value_range;
+        let colormap_data = [value_min, value_max, self.bands as f32, 0.0];
+        queue.write_buffer(&self.colormap_uniform_buffer, 0, cast_slice(&colormap_data));
+
+        let size = [self.init.config.width, self.init.config.height];
+        let layout = self.colorbar.map(|colorbar| (colorbar, self.value_range, size));
+        if layout != self.colorbar_layout {
+            self.colorbar_layout = layout;
+            let vertices = match layout {
+                Some((colorbar, value_range, [width, height])) => {
+                    colorbar.vertices(width, height, value_range)
+                }
+                None => Vec::new(),
+            };
+            self.num_colorbar_vertices = vertices.len() as u32;
+            self.colorbar_buffer = layout.map(|_| {
+                self.init
+                    .device
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some("Colorbar Vertex Buffer"),
+                        contents: cast_slice(&vertices),
+                        usage: wgpu::BufferUsages::VERTEX,
+                    })
+            });
+        }
+    }
+
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.init.get_current_frame()?;
+        let view = output
+            .texture()
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let depth_texture = self.init.device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: self.init.config.width,
+                height: self.init.config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.projection.depth_format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            label: None,
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder =
+            self.init
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Render Encoder"),
+                });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.2,
+                            g: 0.247,
+                            b: 0.314,
+                            a: 1.0,
+                        }),
+                        store: true,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(self.projection.depth_clear()),
+                        store: false,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_index_buffer(self.index_buffer.slice(..), self.index_format);
+            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+
+            if self.show_wireframe {
+                render_pass.set_pipeline(&self.wire_pipeline);
+                render_pass
+                    .set_index_buffer(self.line_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..self.num_line_indices, 0, 0..1);
+            }
+
+            if let Some(colorbar_buffer) = &self.colorbar_buffer {
+                render_pass.set_pipeline(&self.colorbar_pipeline);
+                render_pass.set_vertex_buffer(0, colorbar_buffer.slice(..));
+                render_pass.draw(0..self.num_colorbar_vertices, 0..1);
+            }
+        }
+
+        self.init.queue.submit(iter::once(encoder.finish()));
+        if let Err(e) = self.init.finish_frame(output) {
+            eprintln!("{:?}", e);
+        }
+
+        Ok(())
+    }
+}
+
+fn main() {
+    // usage: ch10_surface_viewer [surface name] [colormap name], or --list for the surfaces and
+    // colormaps.
+    // the colormap name takes options, e.g. jet_r:oklab:10 (see colormap::Colormap::from_name)
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|s| s.as_str()) == Some("--list") {
+        for surface in surfaces::surfaces() {
+            println!("{:<16} default colormap: {}", surface.name, surface.colormap);
+        }
+        println!("colormaps: {}", colormap::names().join(", "));
+        return;
+    }
+    let surface_name = args.get(1).map_or("peaks", |s| s.as_str());
+
+    let mesh = match create_mesh(surface_name) {
+        Ok(mesh) => mesh,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    };
+    let colormap_name = match args.get(2) {
+        Some(name) => name.as_str(),
+        None => surfaces::find(surface_name).unwrap().colormap,
+    };
+    // parametric surfaces are seen from both sides
+    let light_data = common::light([1.0, 1.0, 1.0], 0.1, 0.8, 0.4, 30.0, 1);
+
+    env_logger::init();
+    let event_loop = EventLoop::new();
+    let window = winit::window::WindowBuilder::new()
+        .build(&event_loop)
+        .unwrap();
+    let state = pollster::block_on(State::new(&window, &mesh, light_data, colormap_name));
+    let mut state = match state {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("{:?}", e);
+            std::process::exit(1);
+        }
+    };
+    state.colorbar = Some(Colorbar::new(ColorbarPosition::Right));
+    let title_prefix = format!("ch10_surface_viewer: {}", surface_name);
+    let title = move |state: &State| format!("{} ({})", title_prefix, state.colormap_name);
+    let mut shown_title = title(&state);
+    window.set_title(&shown_title);
+    let capture_file = format!("ch10_surface_viewer_{}.png", surface_name);
+    let render_start_time = std::time::Instant::now();
+    event_loop.run(move |event, _, control_flow| match event {
+        Event::WindowEvent {
+            ref event,
+            window_id,
+        } if window_id == window.id() => {
+            if state.input(event) {
+                if title(&state) != shown_title {
+                    shown_title = title(&state);
+                    window.set_title(&shown_title);
+                }
+            } else {
+                match event {
+                    WindowEvent::CloseRequested
+                    | WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::Escape),
+                                ..
+                            },
+                        ..
+                    } => *control_flow = ControlFlow::Exit,
+                    _ if capture::is_capture_key(event) => {
+                        state.init.request_capture(&capture_file);
+                    }
+                    WindowEvent::Resized(physical_size) => {
+                        state.resize(*physical_size);
+                    }
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        state.resize(**new_inner_size);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Event::RedrawRequested(_) => {
+            state.update(render_start_time.elapsed());
+            match state.render() {
+                Ok(_) => {}
+                Err(wgpu::SurfaceError::Lost) => state.resize(state.init.size),
+                Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+                Err(e) => eprintln!("{:?}", e),
+            }
+        }
+        Event::MainEventsCleared => {
+            window.request_redraw();
+        }
+        _ => {}
+    });
+}
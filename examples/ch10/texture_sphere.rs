@@ -0,0 +1,16 @@
+pub mod common;
+
+fn main() {
+    let mut file_name = "bricks.png";
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 {
+        file_name = &args[1];
+    }
+
+    let mesh = common::sphere_mesh();
+    let light_data = common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let u_mode = wgpu::AddressMode::Repeat;
+    let v_mode = wgpu::AddressMode::ClampToEdge;
+
+    common::run(&mesh, light_data, file_name, u_mode, v_mode, "sphere");
+}
@@ -0,0 +1,26 @@
+pub mod common;
+use hello_world::{expr, math_func};
+
+fn main() {
+    // usage: ch10_texture_surface [texture file, or a procedural texture such as uv]
+    //     ["y formula in x and z", e.g. "cos(x)*sin(z)"]
+    let args: Vec<String> = std::env::args().collect();
+    let file_name = args.get(1).map_or("bricks.png", |s| s.as_str());
+
+    let mesh = match args.get(2) {
+        Some(formula) => match expr::simple_surface(formula) {
+            Ok(f) => common::surface_mesh(&f),
+            Err(e) => {
+                eprintln!("{:?}", e);
+                std::process::exit(1);
+            }
+        },
+        None => common::surface_mesh(&math_func::sinc),
+    };
+    // the light is two-sided so that the underside of the surface is lit as well
+    let light_data = common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let u_mode = wgpu::AddressMode::ClampToEdge;
+    let v_mode = wgpu::AddressMode::ClampToEdge;
+
+    common::run(&mesh, light_data, file_name, u_mode, v_mode, "surface");
+}
@@ -1,5 +1,8 @@
 #![allow(dead_code)]
-use rand::distributions::{Distribution, Uniform};
+use rand::{
+    distributions::{Distribution, Uniform},
+    SeedableRng,
+};
 use wgpu::util::DeviceExt;
 use winit::{
     event::*,
@@ -7,13 +10,12 @@ use winit::{
     window::Window,
     window::WindowBuilder,
 };
-#[path = "../../common/transforms.rs"]
-mod transforms;
+use hello_world::{capture, transforms};
 
 const PARTICLES_PER_GROUP: u32 = 64;
 
-struct State {
-    init: transforms::InitWgpu,
+pub struct State {
+    pub init: transforms::InitWgpu,
     // compute
     position_buffers: Vec<wgpu::Buffer>,
     velocity_buffers: Vec<wgpu::Buffer>,
@@ -51,7 +53,24 @@ impl State {
         mass_factor: Vec<f32>,
     ) -> Self {
         let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(
+            init,
+            num_particles,
+            particle_size,
+            color_opacity,
+            mass_factor,
+            rand::random(),
+        )
+    }
 
+    pub fn with_init(
+        init: transforms::InitWgpu,
+        num_particles: u32,
+        particle_size: f32,
+        color_opacity: f32,
+        mass_factor: Vec<f32>,
+        seed: u64,
+    ) -> Self {
         let shader = init
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -63,7 +82,7 @@ impl State {
         let mut position_data = vec![0.0f32; num_particles as usize * 4];
         let mut velocity_data = vec![0.0f32; num_particles as usize * 4];
         let mut color_data = vec![0.0f32; num_particles as usize * 4];
-        let mut rng = rand::thread_rng();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
         let unif_mp = Uniform::new_inclusive(-1.0, 1.0);
         let unif_p = Uniform::new_inclusive(0.0, 1.0);
 
@@ -380,12 +399,7 @@ impl State {
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.init.size = new_size;
-            self.init.config.width = new_size.width;
-            self.init.config.height = new_size.height;
-            self.init
-                .surface
-                .configure(&self.init.device, &self.init.config);
+            self.init.resize(new_size);
         }
     }
 
@@ -398,10 +412,10 @@ impl State {
         // empty
     }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.init.surface.get_current_texture()?;
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.init.get_current_frame()?;
         let view = output
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
 
         let mut encoder =
@@ -453,7 +467,9 @@ impl State {
         self.frame_num += 1;
 
         self.init.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        if let Err(e) = self.init.finish_frame(output) {
+            eprintln!("{:?}", e);
+        }
 
         Ok(())
     }
@@ -500,6 +516,9 @@ fn main() {
                             },
                         ..
                     } => *control_flow = ControlFlow::Exit,
+                    _ if capture::is_capture_key(event) => {
+                        state.init.request_capture("ch13_attractors.png");
+                    }
                     WindowEvent::Resized(physical_size) => {
                         state.resize(*physical_size);
                     }
@@ -11,13 +11,12 @@ use winit::{
     window::Window,
     window::WindowBuilder,
 };
-#[path = "../../common/transforms.rs"]
-mod transforms;
+use hello_world::{capture, transforms};
 
 const NUM_PARTICLES: u32 = 5000;
 const PARTICLES_PER_GROUP: u32 = 64;
-struct State {
-    init: transforms::InitWgpu,
+pub struct State {
+    pub init: transforms::InitWgpu,
     particle_bind_groups: Vec<wgpu::BindGroup>,
     particle_buffers: Vec<wgpu::Buffer>,
     vertices_buffer: wgpu::Buffer,
@@ -42,7 +41,10 @@ impl State {
 
     async fn new(window: &Window, color_scale: f32) -> Self {
         let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(init, color_scale, rand::random())
+    }
 
+    pub fn with_init(init: transforms::InitWgpu, color_scale: f32, seed: u64) -> Self {
         let shader = init
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -211,7 +213,7 @@ impl State {
             });
 
         let mut initial_particle_data = vec![0.0f32; (4 * NUM_PARTICLES) as usize];
-        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
         let unif = Uniform::new_inclusive(-1.0, 1.0);
         for particle_instance_chunk in initial_particle_data.chunks_mut(4) {
             particle_instance_chunk[0] = unif.sample(&mut rng); // posx
@@ -272,14 +274,10 @@ impl State {
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.init.size = new_size;
-            self.init.config.width = new_size.width;
-            self.init.config.height = new_size.height;
-            self.init
-                .surface
-                .configure(&self.init.device, &self.init.config);
+            self.init.resize(new_size);
         }
     }
+
     #[allow(unused_variables)]
     fn input(&mut self, event: &WindowEvent) -> bool {
         false
@@ -288,10 +286,10 @@ impl State {
         // empty
     }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.init.surface.get_current_texture()?;
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.init.get_current_frame()?;
         let view = output
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
 
         let mut encoder =
@@ -340,7 +338,9 @@ impl State {
         self.frame_num += 1;
 
         self.init.queue.submit(iter::once(encoder.finish()));
-        output.present();
+        if let Err(e) = self.init.finish_frame(output) {
+            eprintln!("{:?}", e);
+        }
 
         Ok(())
     }
@@ -376,6 +376,9 @@ fn main() {
                             },
                         ..
                     } => *control_flow = ControlFlow::Exit,
+                    _ if capture::is_capture_key(event) => {
+                        state.init.request_capture("ch13_boids.png");
+                    }
                     WindowEvent::Resized(physical_size) => {
                         state.resize(*physical_size);
                     }
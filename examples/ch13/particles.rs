@@ -12,13 +12,12 @@ use winit::{
     window::Window,
     window::WindowBuilder,
 };
-#[path = "../../common/transforms.rs"]
-mod transforms;
+use hello_world::{camera, capture, transforms};
 
 const PARTICLES_PER_GROUP: u32 = 64;
 
-struct State {
-    init: transforms::InitWgpu,
+pub struct State {
+    pub init: transforms::InitWgpu,
 
     // compute
     particle_buffer: wgpu::Buffer,
@@ -30,8 +29,8 @@ struct State {
     num_particles: u32,
 
     // render
-    view_mat: Matrix4<f32>,
-    project_mat: Matrix4<f32>,
+    pub camera: camera::CameraController,
+    extent: [f32; 2],
     uniform_buffer: wgpu::Buffer,
     vertex_buffer: wgpu::Buffer,
     render_bind_group: wgpu::BindGroup,
@@ -58,9 +57,17 @@ impl State {
     }
 
     async fn new(window: &Window, num_particles: u32, particle_size: f32) -> Self {
-        let start = SystemTime::now();
         let init = transforms::InitWgpu::init_wgpu(window).await;
+        Self::with_init(init, num_particles, particle_size, rand::random())
+    }
 
+    pub fn with_init(
+        init: transforms::InitWgpu,
+        num_particles: u32,
+        particle_size: f32,
+        seed: u64,
+    ) -> Self {
+        let start = SystemTime::now();
         let shader = init
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -71,7 +78,7 @@ impl State {
         // compute
 
         let mut particle_data = vec![0.0f32; num_particles as usize * 8];
-        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
         let unif_mp = Uniform::new_inclusive(-1.0, 1.0);
         let unif_p = Uniform::new_inclusive(0.0, 1.0);
         for particle_chunck in particle_data.chunks_mut(8) {
@@ -183,23 +190,15 @@ impl State {
 
         // render
 
-        let camera_position = (0.0, 0.0, 2.0).into();
-        let look_direction = (0.0, 0.0, 0.0).into();
-        let up_direction = cgmath::Vector3::unit_y();
+        // the particles live in pixel coordinates; the camera looks at the center of the
+        // window, so that it orbits around it
         let right = init.config.width as f32;
         let top = init.config.height as f32;
-
-        let (view_mat, project_mat, _vp) = transforms::create_view_projection_ortho(
-            0.0,
-            right,
-            0.0,
-            top,
-            -2.0,
-            3.0,
-            camera_position,
-            look_direction,
-            up_direction,
-        );
+        let camera_position = (0.5 * right, 0.5 * top, 2.0).into();
+        let look_direction = (0.5 * right, 0.5 * top, 0.0).into();
+        let mut camera = camera::CameraController::new(camera_position, look_direction);
+        // drag the particles along with the mouse
+        camera.pan_speed = 0.5;
 
         let uniform_buffer = init.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Uniform Buffer"),
@@ -313,8 +312,8 @@ impl State {
             num_particles,
 
             // render
-            view_mat,
-            project_mat,
+            camera,
+            extent: [right, top],
             uniform_buffer,
             vertex_buffer,
             render_pipeline,
@@ -329,28 +328,34 @@ impl State {
         }
     }
 
-    fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.init.size = new_size;
-            self.init.config.width = new_size.width;
-            self.init.config.height = new_size.height;
-            self.init
-                .surface
-                .configure(&self.init.device, &self.init.config);
+            self.init.resize(new_size);
+            self.extent = [new_size.width as f32, new_size.height as f32];
         }
     }
 
-    #[allow(unused_variables)]
     fn input(&mut self, event: &WindowEvent) -> bool {
-        false
+        self.camera.process_event(event)
     }
 
-    fn update(&mut self) {
-        // empty
+    // orthographic projection of a window sized area around the camera target, one unit per
+    // pixel, so that resizing shows more or less of the plane instead of stretching it. the
+    // depth range leaves room for the particle plane to be turned.
+    pub fn projection(&self) -> Matrix4<f32> {
+        let [w, h] = self.extent.map(|e| 0.5 * e * self.camera.zoom_factor());
+        let depth = self.extent[0].max(self.extent[1]);
+        transforms::OPENGL_TO_WGPU_MATRIX
+            * cgmath::ortho(-w, w, -h, h, -2.0 - depth, 3.0 + depth)
     }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+    fn update(&mut self) {
         let t = self.start.elapsed().unwrap().as_millis() as f32 / 1000.0;
+        self.step(t);
+    }
+
+    // advance the simulation clock to t seconds; the accelerations are re-sampled every 1.5 s
+    pub fn step(&mut self, t: f32) {
         let dt0 = t - self.t0;
         if dt0 >= 1.5 {
             for i in 4..12 {
@@ -361,11 +366,13 @@ impl State {
         let dt1 = t - self.t1;
         self.t1 = t;
         self.particle_uniform_data[2] = dt1;
+    }
 
-        let output = self.init.surface.get_current_texture()?;
+    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let output = self.init.get_current_frame()?;
 
         let view = output
-            .texture
+            .texture()
             .create_view(&wgpu::TextureViewDescriptor::default());
         let mut encoder =
             self.init
@@ -402,8 +409,10 @@ impl State {
                 depth_stencil_attachment: None,
             });
 
-            let project_ref: &[f32; 16] = self.project_mat.as_ref();
-            let view_ref: &[f32; 16] = self.view_mat.as_ref();
+            let project_mat = self.projection();
+            let view_mat = self.camera.view_matrix();
+            let project_ref: &[f32; 16] = project_mat.as_ref();
+            let view_ref: &[f32; 16] = view_mat.as_ref();
             self.init.queue.write_buffer(
                 &self.uniform_buffer,
                 0,
@@ -425,7 +434,9 @@ impl State {
             render_pass.draw(0..4, 0..self.num_particles);
         }
         self.init.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        if let Err(e) = self.init.finish_frame(output) {
+            eprintln!("{:?}", e);
+        }
 
         Ok(())
     }
@@ -467,6 +478,9 @@ fn main() {
                             },
                         ..
                     } => *control_flow = ControlFlow::Exit,
+                    _ if capture::is_capture_key(event) => {
+                        state.init.request_capture("ch13_particles.png");
+                    }
                     WindowEvent::Resized(physical_size) => {
                         state.resize(*physical_size);
                     }
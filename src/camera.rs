@@ -0,0 +1,427 @@
+use cgmath::*;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use winit::event::*;
+
+use crate::transforms;
+
+// keep the camera off the poles, where the up direction would be undefined
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+// an orbit camera around a target point, driven by winit events:
+//   left drag                      orbit
+//   right or middle drag,
+//   or shift + left drag           pan the target in the view plane
+//   wheel, page up / page down     dolly towards or away from the target
+//   arrow keys                     orbit in steps
+//   r                              back to the initial view
+// the up direction is always +y.
+pub struct CameraController {
+    pub target: Point3<f32>,
+    // angle around the y axis, measured from +z towards +x
+    pub yaw: f32,
+    // angle above the xz plane
+    pub pitch: f32,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    // radians per pixel of mouse movement
+    pub rotate_speed: f32,
+    // target movement per pixel, as a fraction of the distance
+    pub pan_speed: f32,
+    // relative change of the distance per wheel line
+    pub zoom_speed: f32,
+    home: (Point3<f32>, f32, f32, f32),
+    rotating: bool,
+    panning: bool,
+    shift: bool,
+    cursor: Option<(f64, f64)>,
+}
+
+impl CameraController {
+    // a camera at eye looking at target
+    pub fn new(eye: Point3<f32>, target: Point3<f32>) -> Self {
+        let offset = eye - target;
+        let distance = offset.magnitude().max(1e-3);
+        let yaw = offset.x.atan2(offset.z);
+        let pitch = (offset.y / distance).asin().clamp(-MAX_PITCH, MAX_PITCH);
+        Self {
+            target,
+            yaw,
+            pitch,
+            distance,
+            min_distance: 0.01 * distance,
+            max_distance: 100.0 * distance,
+            rotate_speed: 0.01,
+            pan_speed: 0.002,
+            zoom_speed: 0.1,
+            home: (target, yaw, pitch, distance),
+            rotating: false,
+            panning: false,
+            shift: false,
+            cursor: None,
+        }
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+        self.target + Vector3::new(cp * sy, sp, cp * cy) * self.distance
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        transforms::create_view(self.eye(), self.target, Vector3::unit_y())
+    }
+
+    // current distance relative to the initial one; orthographic projections, which do not
+    // shrink with distance, can scale their extents with it to zoom
+    pub fn zoom_factor(&self) -> f32 {
+        self.distance / self.home.3
+    }
+
+    pub fn rotate(&mut self, dyaw: f32, dpitch: f32) {
+        self.yaw = (self.yaw + dyaw) % TAU;
+        self.pitch = (self.pitch + dpitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    // move the target by dx to the right and dy up in the view plane
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let forward = (self.target - self.eye()).normalize();
+        let right = forward.cross(Vector3::unit_y()).normalize();
+        let up = right.cross(forward);
+        self.target += right * dx + up * dy;
+    }
+
+    // positive steps move the camera closer
+    pub fn zoom(&mut self, steps: f32) {
+        let distance = self.distance * (1.0 - self.zoom_speed).powf(steps);
+        self.distance = distance.clamp(self.min_distance, self.max_distance);
+    }
+
+    pub fn reset(&mut self) {
+        (self.target, self.yaw, self.pitch, self.distance) = self.home;
+    }
+
+    // end a drag, e.g. when the mouse buttons are no longer seen by this camera
+    pub fn stop(&mut self) {
+        self.rotating = false;
+        self.panning = false;
+    }
+
+    // update the camera from a window event; returns whether the event was used
+    pub fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.shift = modifiers.shift();
+                false
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let pressed = *state == ElementState::Pressed;
+                match button {
+                    MouseButton::Left if pressed && self.shift => self.panning = true,
+                    MouseButton::Left if pressed => self.rotating = true,
+                    MouseButton::Left => (self.rotating, self.panning) = (false, false),
+                    MouseButton::Right | MouseButton::Middle => self.panning = pressed,
+                    MouseButton::Other(_) => return false,
+                }
+                true
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let moved = match self.cursor {
+                    Some((x, y)) if self.rotating || self.panning => {
+                        let (dx, dy) = ((position.x - x) as f32, (position.y - y) as f32);
+                        if self.panning {
+                            let scale = self.pan_speed * self.distance;
+                            self.pan(-dx * scale, dy * scale);
+                        } else {
+                            self.rotate(-dx * self.rotate_speed, dy * self.rotate_speed);
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+                self.cursor = Some((position.x, position.y));
+                moved
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(p) => p.y as f32 / 20.0,
+                };
+                self.zoom(lines);
+                true
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state: ElementState::Pressed,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => {
+                let step = 5f32.to_radians();
+                match key {
+                    VirtualKeyCode::Left => self.rotate(step, 0.0),
+                    VirtualKeyCode::Right => self.rotate(-step, 0.0),
+                    VirtualKeyCode::Up => self.rotate(0.0, step),
+                    VirtualKeyCode::Down => self.rotate(0.0, -step),
+                    VirtualKeyCode::PageUp => self.zoom(1.0),
+                    VirtualKeyCode::PageDown => self.zoom(-1.0),
+                    VirtualKeyCode::R => self.reset(),
+                    _ => return false,
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// a first-person camera that looks along yaw and pitch (with the same angle conventions as the
+// orbit camera) and moves with the keyboard:
+//   w / s      forward / backward along the view direction
+//   a / d      left / right
+//   e / q      up / down
+//   left drag  look around
+//   wheel      change the speed
+// movement is applied in update, scaled by the frame time.
+pub struct FlyCamera {
+    pub position: Point3<f32>,
+    pub yaw: f32,
+    pub pitch: f32,
+    // units per second
+    pub speed: f32,
+    // radians per pixel of mouse movement
+    pub look_speed: f32,
+    // forward, backward, left, right, up, down
+    moving: [bool; 6],
+    looking: bool,
+    cursor: Option<(f64, f64)>,
+}
+
+impl FlyCamera {
+    // a camera at eye looking towards target
+    pub fn new(eye: Point3<f32>, target: Point3<f32>) -> Self {
+        let forward = target - eye;
+        let distance = forward.magnitude().max(1e-3);
+        Self {
+            position: eye,
+            yaw: forward.x.atan2(forward.z),
+            pitch: (forward.y / distance).asin().clamp(-MAX_PITCH, MAX_PITCH),
+            speed: 1.0,
+            look_speed: 0.005,
+            moving: [false; 6],
+            looking: false,
+            cursor: None,
+        }
+    }
+
+    pub fn forward(&self) -> Vector3<f32> {
+        let (sy, cy) = self.yaw.sin_cos();
+        let (sp, cp) = self.pitch.sin_cos();
+        Vector3::new(cp * sy, sp, cp * cy)
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        transforms::create_view(
+            self.position,
+            self.position + self.forward(),
+            Vector3::unit_y(),
+        )
+    }
+
+    pub fn look(&mut self, dyaw: f32, dpitch: f32) {
+        self.yaw = (self.yaw + dyaw) % TAU;
+        self.pitch = (self.pitch + dpitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    // stop all movement, e.g. when the window loses the keyboard
+    pub fn stop(&mut self) {
+        self.moving = [false; 6];
+        self.looking = false;
+    }
+
+    // move for dt seconds with the keys currently held down
+    pub fn update(&mut self, dt: f32) {
+        let forward = self.forward();
+        let right = forward.cross(Vector3::unit_y()).normalize();
+        let directions = [
+            forward,
+            -forward,
+            -right,
+            right,
+            Vector3::unit_y(),
+            -Vector3::unit_y(),
+        ];
+        let velocity = directions
+            .iter()
+            .zip(self.moving)
+            .filter(|(_, moving)| *moving)
+            .fold(Vector3::zero(), |sum, (direction, _)| sum + direction);
+        if velocity.magnitude2() > 0.0 {
+            self.position += velocity.normalize() * self.speed * dt;
+        }
+    }
+
+    // update the camera from a window event; returns whether the event was used
+    pub fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::Focused(false) => {
+                self.stop();
+                false
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.looking = *state == ElementState::Pressed;
+                true
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let moved = match self.cursor {
+                    Some((x, y)) if self.looking => {
+                        let (dx, dy) = ((position.x - x) as f32, (position.y - y) as f32);
+                        self.look(-dx * self.look_speed, -dy * self.look_speed);
+                        true
+                    }
+                    _ => false,
+                };
+                self.cursor = Some((position.x, position.y));
+                moved
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(p) => p.y as f32 / 20.0,
+                };
+                self.speed = (self.speed * 1.25f32.powf(lines)).clamp(0.01, 1000.0);
+                true
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state,
+                        virtual_keycode: Some(key),
+                        ..
+                    },
+                ..
+            } => {
+                let k = match key {
+                    VirtualKeyCode::W => 0,
+                    VirtualKeyCode::S => 1,
+                    VirtualKeyCode::A => 2,
+                    VirtualKeyCode::D => 3,
+                    VirtualKeyCode::E => 4,
+                    VirtualKeyCode::Q => 5,
+                    _ => return false,
+                };
+                self.moving[k] = *state == ElementState::Pressed;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraMode {
+    Orbit,
+    Fly,
+}
+
+// an orbit and a fly camera sharing one view; tab switches between them, and the camera
+// switched to takes over the current eye position and view direction
+pub struct Camera {
+    pub mode: CameraMode,
+    pub orbit: CameraController,
+    pub fly: FlyCamera,
+}
+
+impl Camera {
+    // starts in orbit mode, at eye looking at target
+    pub fn new(eye: Point3<f32>, target: Point3<f32>) -> Self {
+        Self {
+            mode: CameraMode::Orbit,
+            orbit: CameraController::new(eye, target),
+            fly: FlyCamera::new(eye, target),
+        }
+    }
+
+    pub fn eye(&self) -> Point3<f32> {
+        match self.mode {
+            CameraMode::Orbit => self.orbit.eye(),
+            CameraMode::Fly => self.fly.position,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        match self.mode {
+            CameraMode::Orbit => self.orbit.view_matrix(),
+            CameraMode::Fly => self.fly.view_matrix(),
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
+        }
+        match mode {
+            CameraMode::Fly => {
+                // a button released in fly mode would leave the orbit dragging
+                self.orbit.stop();
+                self.fly.position = self.orbit.eye();
+                self.fly.yaw = (self.orbit.yaw + PI) % TAU;
+                self.fly.pitch = -self.orbit.pitch;
+            }
+            CameraMode::Orbit => {
+                // orbit around the point straight ahead, at the previous orbit distance
+                self.fly.stop();
+                self.orbit.target = self.fly.position + self.fly.forward() * self.orbit.distance;
+                self.orbit.yaw = (self.fly.yaw + PI) % TAU;
+                self.orbit.pitch = -self.fly.pitch;
+            }
+        }
+        self.mode = mode;
+    }
+
+    // advance the fly camera by dt seconds
+    pub fn update(&mut self, dt: f32) {
+        if self.mode == CameraMode::Fly {
+            self.fly.update(dt);
+        }
+    }
+
+    pub fn process_event(&mut self, event: &WindowEvent) -> bool {
+        if let WindowEvent::KeyboardInput {
+            input:
+                KeyboardInput {
+                    state: ElementState::Pressed,
+                    virtual_keycode: Some(VirtualKeyCode::Tab),
+                    ..
+                },
+            ..
+        } = event
+        {
+            self.set_mode(match self.mode {
+                CameraMode::Orbit => CameraMode::Fly,
+                CameraMode::Fly => CameraMode::Orbit,
+            });
+            return true;
+        }
+        // both cameras follow the cursor and the modifier keys, so that a drag starts from the
+        // right position and with the right mode
+        if let WindowEvent::CursorMoved { .. } | WindowEvent::ModifiersChanged(_) = event {
+            let orbit = self.orbit.process_event(event);
+            let fly = self.fly.process_event(event);
+            return match self.mode {
+                CameraMode::Orbit => orbit,
+                CameraMode::Fly => fly,
+            };
+        }
+        match self.mode {
+            CameraMode::Orbit => self.orbit.process_event(event),
+            CameraMode::Fly => self.fly.process_event(event),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::*;
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent};
+
+// each row of a texture-to-buffer copy must be a multiple of COPY_BYTES_PER_ROW_ALIGNMENT (256)
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded_bytes_per_row = 4 * width;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded_bytes_per_row.div_ceil(align) * align
+}
+
+// copy a rendered texture into a mapped buffer and return its pixels as an RGBA image.
+// the texture must have been created with TextureUsages::COPY_SRC.
+pub fn capture_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> Result<image::RgbaImage> {
+    capture_mip_level(device, queue, texture, 0, width, height, format)
+}
+
+// as capture_texture, for one mip level; width and height are the size of that level
+pub fn capture_mip_level(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    mip_level: u32,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> Result<image::RgbaImage> {
+    let is_bgra = match format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        _ => bail!("unsupported capture format {:?}", format),
+    };
+
+    let padded_bytes_per_row = padded_bytes_per_row(width);
+    let unpadded_bytes_per_row = 4 * width as usize;
+    let read_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Capture Buffer"),
+        size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Capture Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            mip_level,
+            ..texture.as_image_copy()
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &read_buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: std::num::NonZeroU32::new(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    // map the buffer, then poll the device until the mapping has completed
+    let read_buffer_slice = read_buffer.slice(..);
+    let (tx, rx) = futures_intrusive::channel::shared::oneshot_channel();
+    read_buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        tx.send(result).unwrap();
+    });
+    device.poll(wgpu::Maintain::Wait);
+    pollster::block_on(rx.receive())
+        .ok_or_else(|| anyhow!("capture buffer mapping was cancelled"))??;
+
+    // strip the row padding and swizzle BGRA to RGBA
+    let data = read_buffer_slice.get_mapped_range();
+    let mut pixels: Vec<u8> = Vec::with_capacity(unpadded_bytes_per_row * height as usize);
+    for row in data.chunks(padded_bytes_per_row as usize) {
+        pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+    }
+    drop(data);
+    read_buffer.unmap();
+
+    if is_bgra {
+        for pixel in pixels.chunks_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    image::RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow!("captured data does not match a {}x{} image", width, height))
+}
+
+pub fn save_png(img: &image::RgbaImage, file_name: &str) -> Result<()> {
+    img.save_with_format(file_name, image::ImageFormat::Png)
+        .with_context(|| format!("cannot save {}", file_name))?;
+    Ok(())
+}
+
+// capture a frame rendered with the given surface (or offscreen) configuration into a png file
+pub fn save_frame(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    config: &wgpu::SurfaceConfiguration,
+    file_name: &str,
+) -> Result<()> {
+    let img = capture_texture(device, queue, texture, config.width, config.height, config.format)?;
+    save_png(&img, file_name)
+}
+
+// the key that saves a frame of the examples: F12
+pub fn is_capture_key(event: &WindowEvent) -> bool {
+    matches!(
+        event,
+        WindowEvent::KeyboardInput {
+            input: KeyboardInput {
+                state: ElementState::Pressed,
+                virtual_keycode: Some(VirtualKeyCode::F12),
+                ..
+            },
+            ..
+        }
+    )
+}
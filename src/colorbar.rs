@@ -0,0 +1,327 @@
+use bytemuck::{Pod, Zeroable};
+use std::mem;
+
+// a colorbar legend drawn over a rendered scene: a strip of the colormap with a frame, tick marks
+// and value labels in a small built-in bitmap font. it is laid out as screen-space triangles for
+// a pipeline without depth test to draw after the scene, so it ends up in captured frames too;
+// see examples/ch10/surface_viewer.rs.
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct ColorbarVertex {
+    // normalized device coordinates
+    pub position: [f32; 2],
+    // position along the colormap, interpolated over the strip
+    pub t: f32,
+    // 1 for the frame, ticks and labels, which are drawn in a plain color
+    pub solid: f32,
+}
+
+impl ColorbarVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0=>Float32x2, 1=>Float32, 2=>Float32];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<ColorbarVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// the window edge the colorbar runs along; left and right bars are vertical with the minimum at
+// the bottom, top and bottom bars are horizontal with the minimum on the left
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorbarPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Colorbar {
+    pub position: ColorbarPosition,
+    // number of labelled values, including the minimum and maximum
+    pub ticks: usize,
+    // length of the bar as a fraction of the window side it runs along
+    pub length: f32,
+    // sizes in pixels; each pixel of the 5 x 7 font is drawn font_scale pixels wide
+    pub thickness: f32,
+    pub margin: f32,
+    pub font_scale: f32,
+}
+
+impl Colorbar {
+    pub fn new(position: ColorbarPosition) -> Self {
+        Self {
+            position,
+            ticks: 5,
+            length: 0.6,
+            thickness: 16.0,
+            margin: 16.0,
+            font_scale: 2.0,
+        }
+    }
+
+    // the labelled values, evenly spaced from the minimum to the maximum
+    pub fn tick_values(&self, value_range: [f32; 2]) -> Vec<f32> {
+        let [lo, hi] = value_range;
+        let n = self.ticks.max(2);
+        (0..n)
+            .map(|i| lo + (hi - lo) * i as f32 / (n - 1) as f32)
+            .collect()
+    }
+
+    // the triangles of the colorbar in a width x height window
+    pub fn vertices(&self, width: u32, height: u32, value_range: [f32; 2]) -> Vec<ColorbarVertex> {
+        let (w, h) = (width as f32, height as f32);
+        let mut quads = Quads {
+            width: w,
+            height: h,
+            vertices: Vec::new(),
+        };
+        let vertical = matches!(
+            self.position,
+            ColorbarPosition::Left | ColorbarPosition::Right
+        );
+        let (thick, margin, scale) = (self.thickness, self.margin, self.font_scale);
+        let length = self.length * if vertical { h } else { w };
+        let tick = 0.5 * thick;
+
+        // the strip, in window pixels with y down
+        let (x0, y0) = match self.position {
+            ColorbarPosition::Left => (margin, 0.5 * (h - length)),
+            ColorbarPosition::Right => (w - margin - thick, 0.5 * (h - length)),
+            ColorbarPosition::Top => (0.5 * (w - length), margin),
+            ColorbarPosition::Bottom => (0.5 * (w - length), h - margin - thick),
+        };
+        let (x1, y1) = if vertical {
+            (x0 + thick, y0 + length)
+        } else {
+            (x0 + length, y0 + thick)
+        };
+        if vertical {
+            quads.push([x0, y0, x1, y1], [1.0, 1.0, 0.0, 0.0], 0.0);
+        } else {
+            quads.push([x0, y0, x1, y1], [0.0, 1.0, 1.0, 0.0], 0.0);
+        }
+        // a one pixel frame around it
+        quads.solid([x0 - 1.0, y0 - 1.0, x1 + 1.0, y0]);
+        quads.solid([x0 - 1.0, y1, x1 + 1.0, y1 + 1.0]);
+        quads.solid([x0 - 1.0, y0, x0, y1]);
+        quads.solid([x1, y0, x1 + 1.0, y1]);
+
+        // tick marks on the inner side, and labels beyond them; there are fewer ticks than asked
+        // for when their labels would not fit along the bar
+        let (glyph_w, glyph_h) = (6.0 * scale, 7.0 * scale);
+        let mut ticks = self.ticks.max(2);
+        let labels = loop {
+            let values = Colorbar { ticks, ..*self }.tick_values(value_range);
+            let step = (value_range[1] - value_range[0]).abs() / (ticks - 1) as f32;
+            let labels: Vec<String> = values.iter().map(|&v| label(v, step)).collect();
+            let widest = labels.iter().map(|l| l.len()).max().unwrap_or(0) as f32 * glyph_w;
+            let room = if vertical {
+                glyph_h + 2.0 * scale
+            } else {
+                widest + glyph_w
+            };
+            if ticks == 2 || length / (ticks - 1) as f32 >= room {
+                break labels;
+            }
+            ticks -= 1;
+        };
+        for (i, text) in labels.iter().enumerate() {
+            let f = i as f32 / (labels.len() - 1) as f32;
+            let text_w = text.len() as f32 * glyph_w - scale;
+            let gap = tick + 3.0;
+            // the tick position along the bar, and the top left corner of the label
+            let (mark, corner) = match self.position {
+                ColorbarPosition::Left | ColorbarPosition::Right => {
+                    let y = (y1 - f * length).round();
+                    let (mark, x) = if self.position == ColorbarPosition::Left {
+                        ([x1, y, x1 + tick, y + 1.0], x1 + gap)
+                    } else {
+                        ([x0 - tick, y, x0, y + 1.0], x0 - gap - text_w)
+                    };
+                    let y = (y - 0.5 * glyph_h).min(h - glyph_h).max(0.0);
+                    (mark, [x, y])
+                }
+                ColorbarPosition::Top | ColorbarPosition::Bottom => {
+                    let x = (x0 + f * length).round();
+                    let (mark, y) = if self.position == ColorbarPosition::Top {
+                        ([x, y1, x + 1.0, y1 + tick], y1 + gap)
+                    } else {
+                        ([x, y0 - tick, x + 1.0, y0], y0 - gap - glyph_h)
+                    };
+                    let x = (x - 0.5 * text_w).min(w - text_w).max(0.0);
+                    (mark, [x, y])
+                }
+            };
+            quads.solid(mark);
+            quads.text(text, corner[0].round(), corner[1].round(), scale);
+        }
+        quads.vertices
+    }
+}
+
+// a tick label with enough decimals to tell neighbouring ticks apart, e.g. "-0.25", "12",
+// or "1.5e6" for very large or small values
+pub fn label(value: f32, step: f32) -> String {
+    // a tiny value next to larger ticks is a rounded zero; never print it as "-0"
+    let value = if value.abs() <= 1e-4 * step {
+        0.0
+    } else {
+        value
+    };
+    let magnitude = value.abs().max(step);
+    if magnitude >= 1e5 || (magnitude > 0.0 && magnitude < 1e-3) {
+        return format!("{:.1e}", value);
+    }
+    // two significant digits of the step
+    let decimals = if step > 0.0 {
+        (1.0 - step.log10().floor()).clamp(0.0, 4.0) as usize
+    } else {
+        2
+    };
+    format!("{:.*}", decimals, value)
+}
+
+// 5 x 7 glyphs for the characters of the labels, one row per byte with the leftmost pixel in
+// bit 4
+const GLYPHS: [(char, [u8; 7]); 15] = [
+    (
+        '0',
+        [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '1',
+        [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+    ),
+    (
+        '2',
+        [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+    ),
+    (
+        '3',
+        [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '4',
+        [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+    ),
+    (
+        '5',
+        [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '6',
+        [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '7',
+        [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+    ),
+    (
+        '8',
+        [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+    ),
+    (
+        '9',
+        [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+    ),
+    (
+        '-',
+        [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+    ),
+    (
+        '+',
+        [
+            0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000,
+        ],
+    ),
+    (
+        '.',
+        [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+    ),
+    (
+        'e',
+        [
+            0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110,
+        ],
+    ),
+    (' ', [0; 7]),
+];
+
+pub fn glyph(c: char) -> Option<[u8; 7]> {
+    GLYPHS.iter().find(|(g, _)| *g == c).map(|(_, rows)| *rows)
+}
+
+// collects screen rectangles as pairs of triangles
+struct Quads {
+    width: f32,
+    height: f32,
+    vertices: Vec<ColorbarVertex>,
+}
+
+impl Quads {
+    // rect is [left, top, right, bottom] in pixels, t the colormap position at the top left, top
+    // right, bottom right and bottom left corners
+    fn push(&mut self, rect: [f32; 4], t: [f32; 4], solid: f32) {
+        let [l, top, r, b] = rect;
+        let ndc = |x: f32, y: f32| [2.0 * x / self.width - 1.0, 1.0 - 2.0 * y / self.height];
+        let corners = [ndc(l, top), ndc(r, top), ndc(r, b), ndc(l, b)];
+        for k in [0, 3, 1, 1, 3, 2] {
+            self.vertices.push(ColorbarVertex {
+                position: corners[k],
+                t: t[k],
+                solid,
+            });
+        }
+    }
+
+    fn solid(&mut self, rect: [f32; 4]) {
+        self.push(rect, [0.0; 4], 1.0);
+    }
+
+    // each lit glyph pixel is a scale x scale square; unknown characters are skipped
+    fn text(&mut self, text: &str, x: f32, y: f32, scale: f32) {
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c).unwrap_or([0; 7]);
+            let left = x + i as f32 * 6.0 * scale;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0b10000 >> col) != 0 {
+                        let (px, py) = (left + col as f32 * scale, y + row as f32 * scale);
+                        self.solid([px, py, px + scale, py + scale]);
+                    }
+                }
+            }
+        }
+    }
+}
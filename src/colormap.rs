@@ -0,0 +1,386 @@
+use anyhow::{anyhow, bail, Result};
+
+// the matlab-style colormaps, with 11 stops each. unknown names give jet; use Colormap::from_name
+// to reach every colormap and to have unknown names reported.
+pub fn colormap_data(colormap_name: &str) -> [[f32; 3]; 11] { 
+    let colors = match colormap_name { 
+        "hsv" => [[1.0,0.0,0.0],[1.0,0.5,0.0],[0.97,1.0,0.01],[0.0,0.99,0.04],[0.0,0.98,0.52], 
+            [0.0,0.98,1.0],[0.01,0.49,1.0],[0.03,0.0,0.99],[1.0,0.0,0.96],[1.0,0.0,0.49],[1.0,0.0,0.02]], 
+
+        "hot" => [[0.0,0.0,0.0],[0.3,0.0,0.0],[0.6,0.0,0.0],[0.9,0.0,0.0],[0.93,0.27,0.0], 
+            [0.97,0.55,0.0],[1.0,0.82,0.0],[1.0,0.87,0.25],[1.0,0.91,0.5],[1.0,0.96,0.75],[1.0,1.0,1.0]], 
+
+        "cool" => [[0.49,0.0,0.7],[0.45,0.0,0.85],[0.42,0.15,0.89],[0.38,0.29,0.93],[0.27,0.57,0.91], 
+            [0.0,0.8,0.77],[0.0,0.97,0.57],[0.0,0.98,0.46],[0.0,1.0,0.35],[0.16,1.0,0.03],[0.58,1.0,0.0]], 
+
+        "spring" => [[1.0,0.0,1.0],[1.0,0.1,0.9],[1.0,0.2,0.8],[1.0,0.3,0.7],[1.0,0.4,0.6], 
+            [1.0,0.5,0.5],[1.0,0.6,0.4],[1.0,0.7,0.3],[1.0,0.8,0.2],[1.0,0.9,0.1],[1.0,1.0,0.0]], 
+
+        "summer" => [[0.0,0.5,0.4],[0.1,0.55,0.4],[0.2,0.6,0.4],[0.3,0.65,0.4],[0.4,0.7,0.4], 
+            [0.5,0.75,0.4],[0.6,0.8,0.4],[0.7,0.85,0.4],[0.8,0.9,0.4],[0.9,0.95,0.4],[1.0,1.0,0.4]], 
+
+        "autumn" => [[1.0,0.0,0.0],[1.0,0.1,0.0],[1.0,0.2,0.0],[1.0,0.3,0.0],[1.0,0.4,0.0],[1.0,0.5,0.0], 
+            [1.0,0.6,0.0],[1.0,0.7,0.0],[1.0,0.8,0.0],[1.0,0.9,0.0],[1.0,1.0,0.0]], 
+
+        "winter" => [[0.0,0.0,1.0],[0.0,0.1,0.95],[0.0,0.2,0.9],[0.0,0.3,0.85],[0.0,0.4,0.8], 
+            [0.0,0.5,0.75],[0.0,0.6,0.7],[0.0,0.7,0.65],[0.0,0.8,0.6],[0.0,0.9,0.55],[0.0,1.0,0.5]], 
+
+        "bone" => [[0.0,0.0,0.0],[0.08,0.08,0.11],[0.16,0.16,0.23],[0.25,0.25,0.34],[0.33,0.33,0.45], 
+            [0.41,0.44,0.54],[0.5,0.56,0.62],[0.58,0.67,0.7],[0.66,0.78,0.78],[0.83,0.89,0.89], 
+            [1.0,1.0,1.0]], 
+
+        "cooper" => [[0.0,0.0,0.0],[0.13,0.08,0.05],[0.25,0.16,0.1],[0.38,0.24,0.15],[0.5,0.31,0.2], 
+            [0.62,0.39,0.25],[0.75,0.47,0.3],[0.87,0.55,0.35],[1.0,0.63,0.4],[1.0,0.71,0.45], 
+            [1.0,0.78,0.5]], 
+
+        "greys" => [[0.0,0.0,0.0],[0.1,0.1,0.1],[0.2,0.2,0.2],[0.3,0.3,0.3],[0.4,0.4,0.4],[0.5,0.5,0.5], 
+            [0.6,0.6,0.6],[0.7,0.7,0.7],[0.8,0.8,0.8],[0.9,0.9,0.9],[1.0,1.0,1.0]], 
+        
+            // "jet" as default
+        _ => [[0.0,0.0,0.51],[0.0,0.24,0.67],[0.01,0.49,0.78],[0.01,0.75,0.89],[0.02,1.0,1.0], 
+            [0.51,1.0,0.5],[1.0,1.0,0.0],[0.99,0.67,0.0],[0.99,0.33,0.0],[0.98,0.0,0.0],[0.5,0.0,0.0]], 
+    }; 
+
+    colors
+} 
+
+const CLASSIC: &[&str] = &[
+    "hsv", "hot", "cool", "spring", "summer", "autumn", "winter", "bone", "cooper", "greys", "jet",
+];
+
+// the perceptually uniform maps of matplotlib (viridis to cividis), google's turbo, and the
+// diverging maps of kenneth moreland (coolwarm) and colorbrewer (RdBu)
+const VIRIDIS: &[[f32; 3]] = &[
+    [0.267,0.004,0.329], [0.277,0.082,0.406], [0.282,0.157,0.471], [0.267,0.224,0.513],
+    [0.243,0.286,0.537], [0.22,0.349,0.551], [0.192,0.408,0.557], [0.168,0.459,0.558],
+    [0.149,0.51,0.557], [0.126,0.565,0.55], [0.122,0.62,0.537], [0.15,0.67,0.513],
+    [0.208,0.718,0.475], [0.305,0.765,0.419], [0.431,0.808,0.345], [0.564,0.843,0.256],
+    [0.71,0.871,0.169], [0.866,0.89,0.118], [0.992,0.906,0.145],
+];
+
+const MAGMA: &[[f32; 3]] = &[
+    [0.0,0.0,0.016], [0.031,0.034,0.13], [0.094,0.059,0.239], [0.177,0.059,0.364],
+    [0.267,0.059,0.463], [0.356,0.088,0.499], [0.447,0.122,0.506], [0.533,0.15,0.512],
+    [0.62,0.184,0.498], [0.714,0.213,0.473], [0.804,0.251,0.443], [0.882,0.307,0.397],
+    [0.945,0.376,0.365], [0.98,0.475,0.373], [0.992,0.588,0.408], [0.998,0.689,0.468],
+    [0.996,0.792,0.553], [0.979,0.908,0.652], [0.988,0.992,0.749],
+];
+
+const INFERNO: &[[f32; 3]] = &[
+    [0.0,0.0,0.016], [0.034,0.029,0.157], [0.106,0.047,0.255], [0.196,0.045,0.351],
+    [0.29,0.047,0.42], [0.382,0.075,0.434], [0.471,0.11,0.427], [0.56,0.138,0.412],
+    [0.647,0.173,0.376], [0.732,0.215,0.332], [0.812,0.267,0.275], [0.877,0.333,0.207],
+    [0.929,0.412,0.145], [0.967,0.503,0.066], [0.984,0.608,0.024], [0.982,0.712,0.098],
+    [0.969,0.82,0.239], [0.961,0.929,0.401], [0.988,1.0,0.643],
+];
+
+const PLASMA: &[[f32; 3]] = &[
+    [0.051,0.031,0.529], [0.169,0.028,0.578], [0.275,0.012,0.624], [0.364,0.002,0.652],
+    [0.447,0.004,0.659], [0.532,0.038,0.648], [0.612,0.09,0.62], [0.679,0.15,0.576],
+    [0.741,0.216,0.525], [0.797,0.279,0.473], [0.847,0.341,0.42], [0.892,0.408,0.372],
+    [0.929,0.475,0.325], [0.961,0.546,0.276], [0.984,0.624,0.227], [0.995,0.704,0.185],
+    [0.992,0.792,0.149], [0.979,0.891,0.121], [0.941,0.976,0.129],
+];
+
+const CIVIDIS: &[[f32; 3]] = &[
+    [0.0,0.133,0.306], [0.071,0.208,0.439], [0.231,0.286,0.424], [0.341,0.365,0.427],
+    [0.439,0.443,0.451], [0.541,0.529,0.475], [0.651,0.616,0.459], [0.769,0.71,0.424],
+    [0.894,0.812,0.357], [0.996,0.91,0.22],
+];
+
+const TURBO: &[[f32; 3]] = &[
+    [0.188,0.071,0.231], [0.255,0.271,0.671], [0.275,0.459,0.929], [0.224,0.635,0.988],
+    [0.106,0.812,0.831], [0.141,0.925,0.651], [0.38,0.988,0.424], [0.643,0.988,0.231],
+    [0.82,0.91,0.204], [0.953,0.776,0.227], [0.996,0.608,0.176], [0.953,0.388,0.082],
+    [0.851,0.22,0.024], [0.694,0.098,0.004], [0.478,0.016,0.008],
+];
+
+const COOLWARM: &[[f32; 3]] = &[
+    [0.231,0.298,0.753], [0.302,0.408,0.843], [0.384,0.51,0.918], [0.467,0.604,0.969],
+    [0.553,0.69,0.996], [0.639,0.761,1.0], [0.722,0.816,0.976], [0.8,0.851,0.933],
+    [0.867,0.867,0.867], [0.925,0.827,0.773], [0.961,0.769,0.678], [0.969,0.694,0.58],
+    [0.957,0.604,0.482], [0.925,0.498,0.388], [0.871,0.376,0.302], [0.796,0.243,0.22],
+    [0.706,0.016,0.149],
+];
+
+const RDBU: &[[f32; 3]] = &[
+    [0.404,0.0,0.122], [0.698,0.094,0.169], [0.839,0.376,0.302], [0.957,0.647,0.51],
+    [0.992,0.859,0.78], [0.969,0.969,0.969], [0.82,0.898,0.941], [0.573,0.773,0.871],
+    [0.263,0.576,0.765], [0.129,0.4,0.675], [0.02,0.188,0.38],
+];
+
+const SCIENTIFIC: &[(&str, &[[f32; 3]])] = &[
+    ("viridis", VIRIDIS),
+    ("magma", MAGMA),
+    ("inferno", INFERNO),
+    ("plasma", PLASMA),
+    ("cividis", CIVIDIS),
+    ("turbo", TURBO),
+    ("coolwarm", COOLWARM),
+    ("RdBu", RDBU),
+];
+
+pub fn names() -> Vec<&'static str> {
+    CLASSIC.iter().copied().chain(SCIENTIFIC.iter().map(|(name, _)| *name)).collect()
+}
+
+// the stops of a built-in colormap; names are not case sensitive
+fn stops(name: &str) -> Option<Vec<[f32; 3]>> {
+    if let Some(name) = CLASSIC.iter().find(|n| n.eq_ignore_ascii_case(name)) {
+        return Some(colormap_data(name).to_vec());
+    }
+    SCIENTIFIC
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, stops)| stops.to_vec())
+}
+
+// interpolate the named colormap, as t goes from min to max. the name may carry the options
+// described at Colormap::from_name; unknown names give jet, as with colormap_data. this parses the
+// name on every call, so resolve the colormap once with Colormap::from_name to color many points.
+pub fn color_lerp(colormap_name: &str, min: f32, max: f32, t: f32) -> [f32; 3] {
+    Colormap::from_name_or_jet(colormap_name).color_lerp(min, max, t)
+}
+
+// the color space colors are blended in between two stops
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    // straight on the srgb values, as the colormaps have always been drawn
+    #[default]
+    Srgb,
+    LinearRgb,
+    // perceptually even steps in lightness and hue
+    Oklab,
+    // along the shorter way around the hue circle
+    Hsv,
+}
+
+impl Interpolation {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "srgb" => Some(Self::Srgb),
+            "linear" => Some(Self::LinearRgb),
+            "oklab" => Some(Self::Oklab),
+            "hsv" => Some(Self::Hsv),
+            _ => None,
+        }
+    }
+}
+
+// a colormap made of any number (at least one) of evenly spaced srgb stops
+#[derive(Clone, Debug, PartialEq)]
+pub struct Colormap {
+    pub stops: Vec<[f32; 3]>,
+    pub interpolation: Interpolation,
+    // flip the map, so that t = 0 gives the last stop
+    pub reversed: bool,
+    // draw the map as this many flat bands instead of a smooth gradient
+    pub bands: Option<usize>,
+}
+
+// jet, which colormap_data also gives for unknown names
+impl Default for Colormap {
+    fn default() -> Self {
+        Self::new(colormap_data("jet").to_vec())
+    }
+}
+
+impl Colormap {
+    pub fn new(stops: Vec<[f32; 3]>) -> Self {
+        assert!(!stops.is_empty(), "a colormap needs at least one stop");
+        Self {
+            stops,
+            interpolation: Interpolation::Srgb,
+            reversed: false,
+            bands: None,
+        }
+    }
+
+    // a built-in colormap by name, optionally followed by "_r" to reverse it and by ":" separated
+    // options: an interpolation space (srgb, linear, oklab or hsv) or a number of bands.
+    // e.g. "jet", "hot_r", "viridis:oklab" or "RdBu_r:hsv:8".
+    pub fn from_name(name: &str) -> Result<Self> {
+        let mut parts = name.split(':');
+        let base = parts.next().unwrap_or_default();
+        let (stops, reversed) = match (stops(base), base.strip_suffix("_r")) {
+            (Some(stops), _) => (stops, false),
+            (None, Some(base)) => (stops(base).ok_or_else(|| unknown(base))?, true),
+            (None, None) => return Err(unknown(base)),
+        };
+        let mut colormap = Self::new(stops);
+        colormap.reversed = reversed;
+        for option in parts {
+            if let Some(interpolation) = Interpolation::from_name(option) {
+                colormap.interpolation = interpolation;
+            } else if let Some(bands) = option.parse().ok().filter(|&n: &usize| n > 0) {
+                colormap.bands = Some(bands);
+            } else {
+                bail!(
+                    "unknown option '{}' in colormap '{}', expected srgb, linear, oklab, hsv or \
+                     a number of bands",
+                    option,
+                    name
+                );
+            }
+        }
+        Ok(colormap)
+    }
+
+    // as from_name, with jet for names that are not known
+    pub fn from_name_or_jet(name: &str) -> Self {
+        Self::from_name(name).unwrap_or_default()
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn reversed(mut self) -> Self {
+        self.reversed = !self.reversed;
+        self
+    }
+
+    pub fn discrete(mut self, bands: usize) -> Self {
+        self.bands = Some(bands.max(1));
+        self
+    }
+
+    // the srgb color at t in [0, 1]
+    pub fn color(&self, t: f32) -> [f32; 3] {
+        let mut t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        if let Some(bands) = self.bands.filter(|&n| n > 0) {
+            // each band takes the color at its own position along the map, so the first and last
+            // bands keep the end colors
+            let band = ((t * bands as f32) as usize).min(bands - 1);
+            t = if bands == 1 { 0.5 } else { band as f32 / (bands - 1) as f32 };
+        }
+        if self.reversed {
+            t = 1.0 - t;
+        }
+
+        let segments = self.stops.len() - 1;
+        let x = t * segments as f32;
+        let i = (x.floor() as usize).min(segments);
+        if i == segments || x == i as f32 {
+            return self.stops[i];
+        }
+        mix(self.interpolation, self.stops[i], self.stops[i + 1], x - i as f32)
+    }
+
+    // the color of t as it goes from min to max
+    pub fn color_lerp(&self, min: f32, max: f32, t: f32) -> [f32; 3] {
+        let tn = if max > min { (t - min) / (max - min) } else { 0.0 };
+        self.color(tn)
+    }
+}
+
+fn unknown(name: &str) -> anyhow::Error {
+    anyhow!("unknown colormap '{}', expected one of: {}", name, names().join(", "))
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|k| a[k] + (b[k] - a[k]) * t)
+}
+
+fn mix(interpolation: Interpolation, a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    match interpolation {
+        Interpolation::Srgb => lerp3(a, b, t),
+        Interpolation::LinearRgb => {
+            linear_to_srgb(lerp3(srgb_to_linear(a), srgb_to_linear(b), t))
+        }
+        Interpolation::Oklab => {
+            let (a, b) = (linear_to_oklab(srgb_to_linear(a)), linear_to_oklab(srgb_to_linear(b)));
+            linear_to_srgb(oklab_to_linear(lerp3(a, b, t)))
+        }
+        Interpolation::Hsv => {
+            let (mut a, b) = (rgb_to_hsv(a), rgb_to_hsv(b));
+            // grays have no hue, so they take the hue of the other end
+            if a[1] == 0.0 {
+                a[0] = b[0];
+            }
+            let mut c = lerp3(a, b, t);
+            let mut dh = b[0] - a[0];
+            if b[1] == 0.0 {
+                dh = 0.0;
+            } else if dh > 0.5 {
+                dh -= 1.0;
+            } else if dh < -0.5 {
+                dh += 1.0;
+            }
+            c[0] = (a[0] + dh * t).rem_euclid(1.0);
+            hsv_to_rgb(c)
+        }
+    }
+}
+
+pub fn srgb_to_linear(c: [f32; 3]) -> [f32; 3] {
+    c.map(|c| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    })
+}
+
+pub fn linear_to_srgb(c: [f32; 3]) -> [f32; 3] {
+    c.map(|c| {
+        let c = c.clamp(0.0, 1.0);
+        if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    })
+}
+
+// https://bottosson.github.io/posts/oklab/, with the constants as published
+#[allow(clippy::excessive_precision)]
+fn linear_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+#[allow(clippy::excessive_precision)]
+fn oklab_to_linear([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    [
+        4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+        -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+        -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+    ]
+}
+
+// hue, saturation and value, all in [0, 1]
+fn rgb_to_hsv([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    [h / 6.0, s, max]
+}
+
+fn hsv_to_rgb([h, s, v]: [f32; 3]) -> [f32; 3] {
+    let f = |n: f32| {
+        let k = (n + h * 6.0) % 6.0;
+        v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    [f(5.0), f(3.0), f(1.0)]
+}
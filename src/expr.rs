@@ -0,0 +1,378 @@
+use anyhow::{bail, Context, Result};
+use std::f32::consts::{E, PI, TAU};
+
+// a small expression language for surface formulas, e.g. "sin(sqrt(x*x+z*z))/sqrt(x*x+z*z)".
+// it knows + - * / and ^ (or **) with the usual precedence, unary minus, parentheses, numbers
+// like 2, 0.5, .5 or 1e-3, the constants pi, e and tau, two variables and the functions below.
+
+type Function = fn(f32) -> f32;
+type Function2 = fn(f32, f32) -> f32;
+
+const FUNCTIONS: &[(&str, Function)] = &[
+    ("sin", f32::sin),
+    ("cos", f32::cos),
+    ("tan", f32::tan),
+    ("asin", f32::asin),
+    ("acos", f32::acos),
+    ("atan", f32::atan),
+    ("sinh", f32::sinh),
+    ("cosh", f32::cosh),
+    ("tanh", f32::tanh),
+    ("exp", f32::exp),
+    ("ln", f32::ln),
+    ("log", f32::ln),
+    ("log2", f32::log2),
+    ("log10", f32::log10),
+    ("sqrt", f32::sqrt),
+    ("cbrt", f32::cbrt),
+    ("abs", f32::abs),
+    ("sign", f32::signum),
+    ("floor", f32::floor),
+    ("ceil", f32::ceil),
+    ("round", f32::round),
+];
+
+const FUNCTIONS2: &[(&str, Function2)] = &[
+    ("atan2", f32::atan2),
+    ("pow", f32::powf),
+    ("min", f32::min),
+    ("max", f32::max),
+    ("hypot", f32::hypot),
+];
+
+const CONSTANTS: &[(&str, f32)] = &[("pi", PI), ("e", E), ("tau", TAU)];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Number(f32),
+    Name,
+    Symbol(char),
+    End,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Number(f32),
+    Variable(usize),
+    Neg(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(Function, Box<Node>),
+    Call2(Function2, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn eval(&self, vars: [f32; 2]) -> f32 {
+        match self {
+            Node::Number(c) => *c,
+            Node::Variable(k) => vars[*k],
+            Node::Neg(a) => -a.eval(vars),
+            Node::Binary(op, a, b) => {
+                let (a, b) = (a.eval(vars), b.eval(vars));
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+            Node::Call(f, a) => f(a.eval(vars)),
+            Node::Call2(f, a, b) => f(a.eval(vars), b.eval(vars)),
+        }
+    }
+}
+
+// a token with the range of characters it was read from
+struct Span {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+// a parse error: the range of characters it points at and the message
+type ParseResult<T> = std::result::Result<T, (usize, usize, String)>;
+
+fn tokenize(chars: &[char]) -> ParseResult<Vec<Span>> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // an exponent, only when digits follow the e
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f32>() {
+                Ok(value) => Token::Number(value),
+                Err(_) => return Err((start, i, format!("invalid number '{}'", text))),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Name
+        } else if c == '*' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            Token::Symbol('^')
+        } else if "+-*/^(),".contains(c) {
+            i += 1;
+            Token::Symbol(c)
+        } else {
+            return Err((start, start + 1, format!("unexpected character '{}'", c)));
+        };
+        spans.push(Span {
+            token,
+            start,
+            end: i,
+        });
+    }
+    spans.push(Span {
+        token: Token::End,
+        start: chars.len(),
+        end: chars.len() + 1,
+    });
+    Ok(spans)
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    spans: Vec<Span>,
+    pos: usize,
+    variables: [&'a str; 2],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Token {
+        self.spans[self.pos].token
+    }
+
+    fn text(&self, span: &Span) -> String {
+        self.chars[span.start..span.end.min(self.chars.len())]
+            .iter()
+            .collect()
+    }
+
+    fn fail<T>(&self, message: &str) -> ParseResult<T> {
+        let span = &self.spans[self.pos];
+        let found = match span.token {
+            Token::End => "end of input".to_string(),
+            _ => format!("'{}'", self.text(span)),
+        };
+        Err((
+            span.start,
+            span.end,
+            format!("{}, found {}", message, found),
+        ))
+    }
+
+    fn expect(&mut self, symbol: char) -> ParseResult<()> {
+        if self.peek() != Token::Symbol(symbol) {
+            return self.fail(&format!("expected '{}'", symbol));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> ParseResult<Node> {
+        let mut node = self.product()?;
+        while let Token::Symbol(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.product()?));
+        }
+        Ok(node)
+    }
+
+    // product := unary (('*' | '/') unary)*
+    fn product(&mut self) -> ParseResult<Node> {
+        let mut node = self.unary()?;
+        while let Token::Symbol(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> ParseResult<Node> {
+        match self.peek() {
+            Token::Symbol('-') => {
+                self.pos += 1;
+                Ok(Node::Neg(Box::new(self.unary()?)))
+            }
+            Token::Symbol('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := primary ('^' unary)?, so that -x^2 = -(x^2) and 2^3^2 = 2^(3^2)
+    fn power(&mut self) -> ParseResult<Node> {
+        let base = self.primary()?;
+        if self.peek() == Token::Symbol('^') {
+            self.pos += 1;
+            return Ok(Node::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    // primary := number | name | name '(' sum (',' sum)* ')' | '(' sum ')'
+    fn primary(&mut self) -> ParseResult<Node> {
+        match self.peek() {
+            Token::Number(value) => {
+                self.pos += 1;
+                Ok(Node::Number(value))
+            }
+            Token::Symbol('(') => {
+                self.pos += 1;
+                let node = self.sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            Token::Name => self.name(),
+            _ => self.fail("expected a number, variable, function or '('"),
+        }
+    }
+
+    fn name(&mut self) -> ParseResult<Node> {
+        let (start, end) = (self.spans[self.pos].start, self.spans[self.pos].end);
+        let name = self.text(&self.spans[self.pos]);
+        self.pos += 1;
+        if self.peek() != Token::Symbol('(') {
+            if let Some(k) = self.variables.iter().position(|&v| v == name) {
+                return Ok(Node::Variable(k));
+            }
+            if let Some(&(_, value)) = CONSTANTS.iter().find(|(c, _)| *c == name) {
+                return Ok(Node::Number(value));
+            }
+            let message = if FUNCTIONS.iter().any(|(f, _)| *f == name)
+                || FUNCTIONS2.iter().any(|(f, _)| *f == name)
+            {
+                format!("function '{}' needs its arguments in parentheses", name)
+            } else {
+                format!(
+                    "unknown variable '{}', expected {} or {} (or one of the constants pi, e, tau)",
+                    name, self.variables[0], self.variables[1]
+                )
+            };
+            return Err((start, end, message));
+        }
+
+        self.pos += 1;
+        let mut args = vec![self.sum()?];
+        while self.peek() == Token::Symbol(',') {
+            self.pos += 1;
+            args.push(self.sum()?);
+        }
+        self.expect(')')?;
+
+        let arity_error = |expected: usize| {
+            let plural = if expected == 1 { "" } else { "s" };
+            let message = format!(
+                "function '{}' takes {} argument{}, found {}",
+                name,
+                expected,
+                plural,
+                args.len()
+            );
+            Err((start, end, message))
+        };
+        if let Some(&(_, f)) = FUNCTIONS.iter().find(|(n, _)| *n == name) {
+            if args.len() != 1 {
+                return arity_error(1);
+            }
+            return Ok(Node::Call(f, Box::new(args.remove(0))));
+        }
+        if let Some(&(_, f)) = FUNCTIONS2.iter().find(|(n, _)| *n == name) {
+            if args.len() != 2 {
+                return arity_error(2);
+            }
+            let b = args.pop().unwrap();
+            let a = args.pop().unwrap();
+            return Ok(Node::Call2(f, Box::new(a), Box::new(b)));
+        }
+        Err((start, end, format!("unknown function '{}'", name)))
+    }
+}
+
+// a parsed formula in two variables
+#[derive(Clone, Debug)]
+pub struct Expr {
+    root: Node,
+}
+
+impl Expr {
+    // parse a formula in the given variables, e.g. ["x", "z"]. the error names the problem and
+    // marks its position under the formula.
+    pub fn parse(text: &str, variables: [&str; 2]) -> Result<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        let parsed = tokenize(&chars).and_then(|spans| {
+            let mut parser = Parser {
+                chars: &chars,
+                spans,
+                pos: 0,
+                variables,
+            };
+            let root = parser.sum()?;
+            if parser.peek() != Token::End {
+                return parser.fail("expected an operator");
+            }
+            Ok(root)
+        });
+        match parsed {
+            Ok(root) => Ok(Self { root }),
+            Err((start, end, message)) => {
+                let marker = " ".repeat(start) + &"^".repeat(end.max(start + 1) - start);
+                bail!(
+                    "{} at column {}\n  {}\n  {}",
+                    message,
+                    start + 1,
+                    text,
+                    marker
+                )
+            }
+        }
+    }
+
+    pub fn eval(&self, a: f32, b: f32) -> f32 {
+        self.root.eval([a, b])
+    }
+}
+
+// y = f(x, z) as a closure for surface_data::simple_surface_data
+pub fn simple_surface(formula: &str) -> Result<impl Fn(f32, f32) -> [f32; 3]> {
+    let y = Expr::parse(formula, ["x", "z"]).context("invalid surface formula")?;
+    Ok(move |x: f32, z: f32| [x, y.eval(x, z), z])
+}
+
+// the x, y and z formulas in u and v as a closure for surface_data::parametric_surface_data
+pub fn parametric_surface(x: &str, y: &str, z: &str) -> Result<impl Fn(f32, f32) -> [f32; 3]> {
+    let mut exprs = Vec::with_capacity(3);
+    for (axis, formula) in ["x", "y", "z"].iter().zip([x, y, z]) {
+        exprs.push(
+            Expr::parse(formula, ["u", "v"])
+                .with_context(|| format!("invalid formula for {}", axis))?,
+        );
+    }
+    Ok(move |u: f32, v: f32| [0, 1, 2].map(|k| exprs[k].eval(u, v)))
+}
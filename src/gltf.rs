@@ -0,0 +1,264 @@
+use anyhow::*;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+
+use crate::mesh::{IndexData, Mesh};
+
+// glTF constants
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const FLOAT: u32 = 5126;
+const TRIANGLES: u32 = 4;
+
+// the base color image of a material and the u/v address modes it is sampled with, e.g. the
+// image a texture_data::Texture was made from and its address_modes
+#[derive(Clone, Copy, Debug)]
+pub struct BaseColorTexture<'a> {
+    pub image: &'a image::RgbaImage,
+    pub address_modes: [wgpu::AddressMode; 2],
+}
+
+// metallic-roughness material, with the KHR_materials_specular parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub specular: f32,
+    pub specular_color: [f32; 3],
+    pub double_sided: bool,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            specular: 1.0,
+            specular_color: [1.0, 1.0, 1.0],
+            double_sided: false,
+        }
+    }
+}
+
+impl Material {
+    // approximate a blinn-phong material as a dielectric: the diffuse intensity scales the
+    // base color, the shininess exponent n becomes the roughness sqrt(sqrt(2 / (n + 2))) and
+    // the specular intensity and color go to KHR_materials_specular. the ambient term has no
+    // counterpart, viewers light the scene themselves.
+    pub fn from_blinn_phong(
+        diffuse: f32,
+        specular: f32,
+        specular_color: [f32; 3],
+        shininess: f32,
+        double_sided: bool,
+    ) -> Self {
+        let d = diffuse.clamp(0.0, 1.0);
+        Self {
+            base_color: [d, d, d, 1.0],
+            metallic: 0.0,
+            roughness: (2.0 / (shininess.max(0.0) + 2.0)).sqrt().sqrt(),
+            specular: specular.clamp(0.0, 1.0),
+            specular_color,
+            double_sided,
+        }
+    }
+}
+
+fn wrap_mode(mode: wgpu::AddressMode) -> u32 {
+    match mode {
+        wgpu::AddressMode::Repeat => 10497,
+        wgpu::AddressMode::MirrorRepeat => 33648,
+        wgpu::AddressMode::ClampToEdge | wgpu::AddressMode::ClampToBorder => 33071,
+    }
+}
+
+// glb chunks and buffer views are aligned to 4 bytes
+fn pad(bytes: &mut Vec<u8>, fill: u8) {
+    bytes.resize(bytes.len().next_multiple_of(4), fill);
+}
+
+// the binary chunk being assembled, with the buffer views and accessors pointing into it
+#[derive(Default)]
+struct Builder {
+    bin: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl Builder {
+    fn push_view(&mut self, data: &[u8], target: Option<u32>) -> usize {
+        // every view starts on a 4-byte boundary, as required for float and u32 components
+        pad(&mut self.bin, 0);
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.bin.len(),
+            "byteLength": data.len(),
+        });
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+        self.bin.extend_from_slice(data);
+        self.buffer_views.push(view);
+        self.buffer_views.len() - 1
+    }
+
+    fn push_floats<const N: usize>(&mut self, values: &[[f32; N]], with_bounds: bool) -> usize {
+        let floats: Vec<f32> = values.iter().flatten().copied().collect();
+        let view = self.push_view(bytemuck::cast_slice(&floats), Some(ARRAY_BUFFER));
+        let kind = match N {
+            2 => "VEC2",
+            3 => "VEC3",
+            _ => "VEC4",
+        };
+        let mut accessor = json!({
+            "bufferView": view,
+            "componentType": FLOAT,
+            "count": values.len(),
+            "type": kind,
+        });
+        // POSITION accessors must carry their bounds
+        if with_bounds {
+            let mut min = [f32::MAX; N];
+            let mut max = [f32::MIN; N];
+            for v in values {
+                for k in 0..N {
+                    min[k] = min[k].min(v[k]);
+                    max[k] = max[k].max(v[k]);
+                }
+            }
+            accessor["min"] = json!(min.to_vec());
+            accessor["max"] = json!(max.to_vec());
+        }
+        self.accessors.push(accessor);
+        self.accessors.len() - 1
+    }
+
+    fn push_indices(&mut self, indices: &IndexData) -> usize {
+        let view = self.push_view(indices.contents(), Some(ELEMENT_ARRAY_BUFFER));
+        let component_type = match indices {
+            IndexData::U16(_) => UNSIGNED_SHORT,
+            IndexData::U32(_) => UNSIGNED_INT,
+        };
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": component_type,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+}
+
+// write a mesh as a binary glTF 2.0 (.glb) scene with a single node. normals, colormap colors
+// (COLOR_0, multiplied with the base color) and uvs are exported when the mesh has them; the
+// texture's image is embedded as png and sampled with its address modes.
+pub fn write_glb<W: Write>(
+    mesh: &Mesh,
+    w: &mut W,
+    material: &Material,
+    texture: Option<BaseColorTexture>,
+) -> Result<()> {
+    ensure!(!mesh.positions.is_empty(), "cannot export an empty mesh");
+    let mut builder = Builder::default();
+
+    let mut attributes = json!({
+        "POSITION": builder.push_floats(&mesh.positions, true),
+    });
+    if mesh.normals.len() == mesh.positions.len() {
+        attributes["NORMAL"] = json!(builder.push_floats(&mesh.normals, false));
+    }
+    if mesh.uvs.len() == mesh.positions.len() {
+        attributes["TEXCOORD_0"] = json!(builder.push_floats(&mesh.uvs, false));
+    }
+    if mesh.colors.len() == mesh.positions.len() {
+        attributes["COLOR_0"] = json!(builder.push_floats(&mesh.colors, false));
+    }
+    let mut primitive = json!({
+        "attributes": attributes,
+        "material": 0,
+        "mode": TRIANGLES,
+    });
+    if let Some(indices) = mesh.index_data() {
+        primitive["indices"] = json!(builder.push_indices(&indices));
+    }
+
+    let mut pbr = json!({
+        "baseColorFactor": material.base_color,
+        "metallicFactor": material.metallic,
+        "roughnessFactor": material.roughness,
+    });
+    let mut doc = json!({
+        "asset": { "version": "2.0", "generator": "hello_world" },
+        "extensionsUsed": ["KHR_materials_specular"],
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "mesh": 0 }],
+        "meshes": [{ "primitives": [primitive] }],
+    });
+
+    if let Some(texture) = texture {
+        let mut png = Cursor::new(Vec::new());
+        texture
+            .image
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .context("cannot encode the texture as png")?;
+        let view = builder.push_view(png.get_ref(), None);
+        doc["images"] = json!([{ "bufferView": view, "mimeType": "image/png" }]);
+        doc["samplers"] = json!([{
+            "wrapS": wrap_mode(texture.address_modes[0]),
+            "wrapT": wrap_mode(texture.address_modes[1]),
+        }]);
+        doc["textures"] = json!([{ "sampler": 0, "source": 0 }]);
+        pbr["baseColorTexture"] = json!({ "index": 0 });
+    }
+    doc["materials"] = json!([{
+        "pbrMetallicRoughness": pbr,
+        "doubleSided": material.double_sided,
+        "extensions": {
+            "KHR_materials_specular": {
+                "specularFactor": material.specular,
+                "specularColorFactor": material.specular_color,
+            }
+        },
+    }]);
+
+    pad(&mut builder.bin, 0);
+    doc["buffers"] = json!([{ "byteLength": builder.bin.len() }]);
+    doc["bufferViews"] = Value::Array(builder.buffer_views);
+    doc["accessors"] = Value::Array(builder.accessors);
+
+    // the json chunk is padded with spaces, the binary chunk with zeros
+    let mut json_chunk = serde_json::to_vec(&doc)?;
+    pad(&mut json_chunk, b' ');
+    let total_length = 12 + 8 + json_chunk.len() + 8 + builder.bin.len();
+
+    w.write_all(b"glTF")?;
+    w.write_all(&2u32.to_le_bytes())?;
+    w.write_all(&(total_length as u32).to_le_bytes())?;
+    w.write_all(&(json_chunk.len() as u32).to_le_bytes())?;
+    w.write_all(b"JSON")?;
+    w.write_all(&json_chunk)?;
+    w.write_all(&(builder.bin.len() as u32).to_le_bytes())?;
+    w.write_all(b"BIN\0")?;
+    w.write_all(&builder.bin)?;
+    Ok(())
+}
+
+pub fn save_glb(
+    mesh: &Mesh,
+    file_name: &str,
+    material: &Material,
+    texture: Option<BaseColorTexture>,
+) -> Result<()> {
+    let mut w = BufWriter::new(
+        File::create(file_name).with_context(|| format!("cannot create {}", file_name))?,
+    );
+    write_glb(mesh, &mut w, material, texture)?;
+    w.flush()?;
+    Ok(())
+}
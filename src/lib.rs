@@ -0,0 +1,21 @@
+//! Shared code for the examples: wgpu setup, transforms and cameras, geometry generators for
+//! simple shapes and surfaces, surface formulas, colormaps and colorbars, image and procedural
+//! textures, frame capture and mesh export.
+
+pub mod camera;
+pub mod capture;
+pub mod colorbar;
+pub mod colormap;
+pub mod expr;
+pub mod gltf;
+pub mod math_func;
+pub mod mesh;
+pub mod obj;
+pub mod ply;
+pub mod procedural;
+pub mod stl;
+pub mod surface_data;
+pub mod surfaces;
+pub mod texture_data;
+pub mod transforms;
+pub mod vertex_data;
@@ -0,0 +1,459 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{InnerSpace, Vector3};
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+// geometry produced by the generators in vertex_data and surface_data. positions, normals,
+// colors and uvs are per vertex; colors and uvs may be left empty when a generator has none.
+// without indices every three vertices form a triangle.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Option<Vec<u32>>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct MeshVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub color: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+impl MeshVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![0=>Float32x3, 1=>Float32x3, 2=>Float32x3, 3=>Float32x2];
+
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+// how a generator assigns vertex normals. Flat gives every quad a single face normal, the
+// weighted modes average the normals of the triangles around a vertex, and Analytic takes them
+// from the partial derivatives of the generating function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NormalMode {
+    #[default]
+    Flat,
+    AreaWeighted,
+    AngleWeighted,
+    Analytic,
+}
+
+// index buffer contents in the narrowest format that can address every vertex
+pub enum IndexData {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl IndexData {
+    pub fn format(&self) -> wgpu::IndexFormat {
+        match self {
+            IndexData::U16(_) => wgpu::IndexFormat::Uint16,
+            IndexData::U32(_) => wgpu::IndexFormat::Uint32,
+        }
+    }
+
+    pub fn contents(&self) -> &[u8] {
+        match self {
+            IndexData::U16(indices) => bytemuck::cast_slice(indices),
+            IndexData::U32(indices) => bytemuck::cast_slice(indices),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            IndexData::U16(indices) => indices.len(),
+            IndexData::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// triangle indices for a grid of nu x nv vertices stored row by row (index i * nv + j). each
+// cell is split into the triangles (c0, c1, c2) and (c2, c3, c0), where the corners are given as
+// (di, dj) offsets from the cell origin so that generators can keep their original winding.
+// a grid with fewer than two vertices along either side has no cells and no indices.
+pub fn grid_indices(nu: usize, nv: usize, corners: [(usize, usize); 4]) -> Vec<u32> {
+    let (cells_u, cells_v) = (nu.saturating_sub(1), nv.saturating_sub(1));
+    let mut indices: Vec<u32> = Vec::with_capacity(6 * cells_u * cells_v);
+    for i in 0..cells_u {
+        for j in 0..cells_v {
+            let c = corners.map(|(di, dj)| ((i + di) * nv + j + dj) as u32);
+            indices.extend_from_slice(&[c[0], c[1], c[2], c[2], c[3], c[0]]);
+        }
+    }
+    indices
+}
+
+// per-vertex normals of an indexed triangle list, averaged from the face normals of the
+// triangles sharing each vertex, weighted by triangle area or by the angle at the vertex.
+// degenerate triangles add nothing; a vertex without any usable triangle gets (0, 1, 0).
+pub fn smooth_normals(
+    positions: &[[f32; 3]],
+    indices: &[u32],
+    angle_weighted: bool,
+) -> Vec<[f32; 3]> {
+    let mut sums = vec![Vector3::new(0.0f32, 0.0, 0.0); positions.len()];
+    for tri in indices.chunks_exact(3) {
+        let p = [0, 1, 2].map(|k| Vector3::from(positions[tri[k] as usize]));
+        // the cross product has length twice the triangle area
+        let cp = (p[1] - p[0]).cross(p[2] - p[0]);
+        if cp.magnitude2() == 0.0 {
+            continue;
+        }
+        for k in 0..3 {
+            let weight = if angle_weighted {
+                let e1 = p[(k + 1) % 3] - p[k];
+                let e2 = p[(k + 2) % 3] - p[k];
+                e1.angle(e2).0 / cp.magnitude()
+            } else {
+                1.0
+            };
+            sums[tri[k] as usize] += cp * weight;
+        }
+    }
+    sums.iter()
+        .map(|&n| {
+            if n.magnitude2() > 0.0 {
+                n.normalize().into()
+            } else {
+                [0.0, 1.0, 0.0]
+            }
+        })
+        .collect()
+}
+
+// merges points closer than eps; every cell of the hash grid is eps wide, so a match is
+// always in one of the 27 cells around a point
+struct Welder {
+    eps: f32,
+    cells: HashMap<[i64; 3], Vec<usize>>,
+    points: Vec<Vector3<f32>>,
+}
+
+impl Welder {
+    fn new(eps: f32) -> Self {
+        Self {
+            eps,
+            cells: HashMap::new(),
+            points: Vec::new(),
+        }
+    }
+
+    fn cell(&self, p: Vector3<f32>) -> [i64; 3] {
+        [p.x, p.y, p.z].map(|c| (c / self.eps).floor() as i64)
+    }
+
+    // indices of the stored points within eps of p
+    fn near(&self, p: Vector3<f32>) -> Vec<usize> {
+        let [x, y, z] = self.cell(p);
+        let mut found = Vec::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(ids) = self.cells.get(&[x + dx, y + dy, z + dz]) {
+                        found.extend(
+                            ids.iter()
+                                .filter(|&&i| (self.points[i] - p).magnitude() <= self.eps),
+                        );
+                    }
+                }
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn insert(&mut self, p: Vector3<f32>) -> usize {
+        let id = self.points.len();
+        self.points.push(p);
+        self.cells.entry(self.cell(p)).or_default().push(id);
+        id
+    }
+
+    // the first stored point within eps of p, or p itself as a new point
+    fn weld(&mut self, p: Vector3<f32>) -> usize {
+        match self.near(p).first() {
+            Some(&id) => id,
+            None => self.insert(p),
+        }
+    }
+}
+
+impl Mesh {
+    pub fn num_vertices(&self) -> usize {
+        self.positions.len()
+    }
+
+    // number of elements to draw: the index count for indexed meshes, the vertex count otherwise
+    pub fn num_elements(&self) -> usize {
+        match &self.indices {
+            Some(indices) => indices.len(),
+            None => self.positions.len(),
+        }
+    }
+
+    // vertex indices of each triangle, whether or not the mesh is indexed
+    pub fn triangles(&self) -> Vec<[u32; 3]> {
+        match &self.indices {
+            Some(indices) => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            None => (0..self.positions.len() as u32 / 3)
+                .map(|t| [3 * t, 3 * t + 1, 3 * t + 2])
+                .collect(),
+        }
+    }
+
+    // the triangle edges as pairs of vertex indices, each shared edge only once, e.g. for a
+    // wireframe drawn as a line list. edges of an unindexed mesh are only shared when they
+    // use the same vertices.
+    pub fn edges(&self) -> Vec<[u32; 2]> {
+        let mut seen = HashSet::new();
+        let mut edges = Vec::new();
+        for [a, b, c] in self.triangles() {
+            for (i, j) in [(a, b), (b, c), (c, a)] {
+                if seen.insert((i.min(j), i.max(j))) {
+                    edges.push([i, j]);
+                }
+            }
+        }
+        edges
+    }
+
+    // expand an indexed mesh into one vertex per triangle corner, e.g. for pipelines drawn
+    // without an index buffer
+    pub fn unindexed(&self) -> Mesh {
+        let Some(indices) = &self.indices else {
+            return self.clone();
+        };
+        fn pick<T: Copy>(values: &[T], indices: &[u32]) -> Vec<T> {
+            if values.is_empty() {
+                return Vec::new();
+            }
+            indices.iter().map(|&i| values[i as usize]).collect()
+        }
+        Mesh {
+            positions: pick(&self.positions, indices),
+            normals: pick(&self.normals, indices),
+            colors: pick(&self.colors, indices),
+            uvs: pick(&self.uvs, indices),
+            indices: None,
+        }
+    }
+
+    // turn a (possibly open) surface into a closed solid shell of the given wall thickness, e.g.
+    // for 3d printing. coincident vertices are welded first, so that the seams of closed
+    // parametric surfaces stay closed, while an orientation flip (as in the klein bottle) is
+    // resolved by joining the two sides of the wall.
+    // the surface is offset by half the thickness along the vertex normals on each side and every
+    // boundary edge is closed with a strip. only positions and normals are kept.
+    pub fn thicken(&self, thickness: f32) -> Mesh {
+        let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+        for p in &self.positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        let diagonal = (Vector3::from(max) - Vector3::from(min)).magnitude();
+        let eps = 1e-5 * diagonal.max(thickness);
+
+        // weld coincident points and drop the triangles that collapse
+        let mut point_welder = Welder::new(eps);
+        let mut welded: Vec<[usize; 3]> = Vec::new();
+        let mut face_normals: Vec<Vector3<f32>> = Vec::new();
+        for tri in self.triangles() {
+            let ids = tri.map(|i| point_welder.weld(Vector3::from(self.positions[i as usize])));
+            let p = ids.map(|id| point_welder.points[id]);
+            let cp = (p[1] - p[0]).cross(p[2] - p[0]);
+            if ids[0] != ids[1] && ids[1] != ids[2] && ids[2] != ids[0] && cp.magnitude2() > 0.0 {
+                welded.push(ids);
+                face_normals.push(cp);
+            }
+        }
+
+        // split the points into vertices: the corners of two triangles at a point are joined
+        // when the triangles share an edge that they run along in opposite directions. across an
+        // orientation flip both run the same way, so the two sides keep separate vertices.
+        let mut edge_uses: HashMap<(usize, usize), Vec<[usize; 2]>> = HashMap::new();
+        for (t, ids) in welded.iter().enumerate() {
+            for k in 0..3 {
+                let l = (k + 1) % 3;
+                // the corners (3 * triangle + k) at the smaller and at the larger point
+                let corners = if ids[k] < ids[l] {
+                    [3 * t + k, 3 * t + l]
+                } else {
+                    [3 * t + l, 3 * t + k]
+                };
+                edge_uses
+                    .entry((ids[k].min(ids[l]), ids[k].max(ids[l])))
+                    .or_default()
+                    .push(corners);
+            }
+        }
+        let mut parents: Vec<usize> = (0..3 * welded.len()).collect();
+        fn root(parents: &mut [usize], mut i: usize) -> usize {
+            while parents[i] != i {
+                parents[i] = parents[parents[i]];
+                i = parents[i];
+            }
+            i
+        }
+        // whether the triangle runs from corner c to corner d
+        let forward = |[c, d]: [usize; 2]| d == 3 * (c / 3) + (c % 3 + 1) % 3;
+        for uses in edge_uses.values() {
+            if let [first, second] = uses[..] {
+                if forward(first) != forward(second) {
+                    for (c1, c2) in first.into_iter().zip(second) {
+                        let (r1, r2) = (root(&mut parents, c1), root(&mut parents, c2));
+                        parents[r1] = r2;
+                    }
+                }
+            }
+        }
+        let mut vertex_of_root: HashMap<usize, usize> = HashMap::new();
+        let mut clusters: Vec<usize> = Vec::new();
+        let mut sums: Vec<Vector3<f32>> = Vec::new();
+        let mut triangles: Vec<[usize; 3]> = Vec::with_capacity(welded.len());
+        for (t, ids) in welded.iter().enumerate() {
+            let tri = [0, 1, 2].map(|k| {
+                let r = root(&mut parents, 3 * t + k);
+                let v = *vertex_of_root.entry(r).or_insert_with(|| {
+                    clusters.push(ids[k]);
+                    sums.push(Vector3::new(0.0, 0.0, 0.0));
+                    clusters.len() - 1
+                });
+                sums[v] += face_normals[t];
+                v
+            });
+            triangles.push(tri);
+        }
+
+        // vertices at the same point but facing opposite ways (the two sides of a flip) share
+        // one normal up to sign, so that the offset layers of both sides meet exactly
+        let mut references: Vec<Option<Vector3<f32>>> = vec![None; point_welder.points.len()];
+        let mut cluster_sums = vec![Vector3::new(0.0f32, 0.0, 0.0); point_welder.points.len()];
+        let mut signs: Vec<f32> = Vec::with_capacity(sums.len());
+        for (v, &sum) in sums.iter().enumerate() {
+            let c = clusters[v];
+            let reference = *references[c].get_or_insert(sum);
+            let sign = if sum.dot(reference) >= 0.0 { 1.0 } else { -1.0 };
+            cluster_sums[c] += sum * sign;
+            signs.push(sign);
+        }
+        let points: Vec<Vector3<f32>> = clusters.iter().map(|&c| point_welder.points[c]).collect();
+        let normals: Vec<Vector3<f32>> = (0..sums.len())
+            .map(|v| cluster_sums[clusters[v]].normalize() * signs[v])
+            .collect();
+
+        // outer and inner layers, the inner one with reversed winding
+        let n = points.len();
+        let mut shell: Vec<Vector3<f32>> = Vec::with_capacity(2 * n);
+        shell.extend((0..n).map(|i| points[i] + normals[i] * (0.5 * thickness)));
+        shell.extend((0..n).map(|i| points[i] - normals[i] * (0.5 * thickness)));
+        let mut faces: Vec<[usize; 3]> = Vec::with_capacity(2 * triangles.len());
+        faces.extend(triangles.iter().copied());
+        faces.extend(triangles.iter().map(|&[a, b, c]| [n + a, n + c, n + b]));
+
+        // close every boundary edge a -> b with the quad b, a, a', b' between the layers.
+        // at an orientation flip the walls of both sides coincide with opposite windings; they
+        // cancel out and the layers are joined by the final weld instead.
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for &[a, b, c] in &triangles {
+            for edge in [(a, b), (b, c), (c, a)] {
+                *edges.entry(edge).or_default() += 1;
+            }
+        }
+        let mut final_welder = Welder::new(eps);
+        let shell_ids: Vec<usize> = shell.iter().map(|&p| final_welder.weld(p)).collect();
+        let mut walls: HashMap<[usize; 4], ([usize; 4], usize)> = HashMap::new();
+        for (&(a, b), &count) in &edges {
+            if edges.contains_key(&(b, a)) || count != 1 {
+                continue;
+            }
+            let quad = [b, a, n + a, n + b];
+            let mut key = quad.map(|i| shell_ids[i]);
+            key.sort_unstable();
+            walls.entry(key).or_insert((quad, 0)).1 += 1;
+        }
+        for (quad, count) in walls.into_values() {
+            if count == 1 {
+                faces.push([quad[0], quad[1], quad[2]]);
+                faces.push([quad[2], quad[3], quad[0]]);
+            }
+        }
+
+        // weld the layers where they meet and drop what collapses
+        let positions: Vec<[f32; 3]> = final_welder.points.iter().map(|&p| p.into()).collect();
+        let indices: Vec<u32> = faces
+            .iter()
+            .map(|f| f.map(|i| shell_ids[i] as u32))
+            .filter(|f| f[0] != f[1] && f[1] != f[2] && f[2] != f[0])
+            .flatten()
+            .collect();
+        let normals = smooth_normals(&positions, &indices, false);
+        Mesh {
+            positions,
+            normals,
+            indices: Some(indices),
+            ..Default::default()
+        }
+    }
+
+    // interleave the attributes into any vertex type; missing normals and colors default to
+    // zero and white, missing uvs to (0, 0)
+    pub fn interleave<V, F>(&self, f: F) -> Vec<V>
+    where
+        F: Fn([f32; 3], [f32; 3], [f32; 3], [f32; 2]) -> V,
+    {
+        (0..self.positions.len())
+            .map(|i| {
+                f(
+                    self.positions[i],
+                    self.normals.get(i).copied().unwrap_or([0.0, 0.0, 0.0]),
+                    self.colors.get(i).copied().unwrap_or([1.0, 1.0, 1.0]),
+                    self.uvs.get(i).copied().unwrap_or([0.0, 0.0]),
+                )
+            })
+            .collect()
+    }
+
+    // interleaved vertices matching MeshVertex::desc()
+    pub fn vertices(&self) -> Vec<MeshVertex> {
+        self.interleave(|position, normal, color, uv| MeshVertex {
+            position,
+            normal,
+            color,
+            uv,
+        })
+    }
+
+    // indices narrowed to u16 when the mesh has few enough vertices, u32 otherwise
+    pub fn index_data(&self) -> Option<IndexData> {
+        let indices = self.indices.as_ref()?;
+        if self.positions.len() <= u16::MAX as usize + 1 {
+            Some(IndexData::U16(indices.iter().map(|&i| i as u16).collect()))
+        } else {
+            Some(IndexData::U32(indices.clone()))
+        }
+    }
+
+    pub fn vertex_buffer_layout<'a>() -> wgpu::VertexBufferLayout<'a> {
+        MeshVertex::desc()
+    }
+}
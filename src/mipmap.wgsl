@@ -0,0 +1,24 @@
+// renders one mip level from the level above, see texture_data::generate_mipmaps
+
+struct Output {
+    @builtin(position) position : vec4<f32>,
+    @location(0)       uv       : vec2<f32>,
+};
+
+// a single triangle covering the whole target
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> Output {
+    var output: Output;
+    let uv: vec2<f32> = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    output.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    output.uv = uv;
+    return output;
+}
+
+@group(0) @binding(0) var source_texture : texture_2d<f32>;
+@group(0) @binding(1) var source_sampler : sampler;
+
+@fragment
+fn fs_main(in: Output) -> @location(0) vec4<f32> {
+    return textureSample(source_texture, source_sampler, in.uv);
+}
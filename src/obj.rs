@@ -0,0 +1,297 @@
+use anyhow::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::mesh::{smooth_normals, Mesh};
+
+// write a mesh as wavefront obj. vertex colors use the common "v x y z r g b" extension read
+// by blender and meshlab; normals and texture coordinates are written when the mesh has them.
+// mtl_file names a material library whose material "mesh" is applied to all faces.
+pub fn write_obj<W: Write>(mesh: &Mesh, w: &mut W, mtl_file: Option<&str>) -> Result<()> {
+    let has_colors = mesh.colors.len() == mesh.positions.len();
+    let has_normals = mesh.normals.len() == mesh.positions.len();
+    let has_uvs = mesh.uvs.len() == mesh.positions.len();
+
+    writeln!(w, "# hello_world mesh: {} vertices", mesh.num_vertices())?;
+    if let Some(mtl_file) = mtl_file {
+        writeln!(w, "mtllib {}", mtl_file)?;
+    }
+    for (i, p) in mesh.positions.iter().enumerate() {
+        if has_colors {
+            let c = mesh.colors[i];
+            writeln!(w, "v {} {} {} {} {} {}", p[0], p[1], p[2], c[0], c[1], c[2])?;
+        } else {
+            writeln!(w, "v {} {} {}", p[0], p[1], p[2])?;
+        }
+    }
+    if has_uvs {
+        for t in &mesh.uvs {
+            // obj puts v = 0 at the bottom of the image, wgpu at the top
+            writeln!(w, "vt {} {}", t[0], 1.0 - t[1])?;
+        }
+    }
+    if has_normals {
+        for n in &mesh.normals {
+            writeln!(w, "vn {} {} {}", n[0], n[1], n[2])?;
+        }
+    }
+
+    if mtl_file.is_some() {
+        writeln!(w, "usemtl mesh")?;
+    }
+    // obj indices start at 1
+    let corner = |i: u32| match (has_uvs, has_normals) {
+        (true, true) => format!("{0}/{0}/{0}", i + 1),
+        (true, false) => format!("{0}/{0}", i + 1),
+        (false, true) => format!("{0}//{0}", i + 1),
+        (false, false) => format!("{}", i + 1),
+    };
+    for [a, b, c] in mesh.triangles() {
+        writeln!(w, "f {} {} {}", corner(a), corner(b), corner(c))?;
+    }
+    Ok(())
+}
+
+// write the material library referenced by write_obj: a white diffuse material, textured
+// with texture_file when given
+pub fn write_mtl<W: Write>(w: &mut W, texture_file: Option<&str>) -> Result<()> {
+    writeln!(w, "newmtl mesh")?;
+    writeln!(w, "Ka 0.1 0.1 0.1")?;
+    writeln!(w, "Kd 1 1 1")?;
+    writeln!(w, "Ks 0.4 0.4 0.4")?;
+    writeln!(w, "Ns 30")?;
+    writeln!(w, "illum 2")?;
+    if let Some(texture_file) = texture_file {
+        writeln!(w, "map_Kd {}", texture_file)?;
+    }
+    Ok(())
+}
+
+// save a mesh to file_name and its material library next to it, with the same stem and a .mtl
+// extension. texture_file is stored in the material as given, so it should be relative to the
+// obj file.
+pub fn save_obj(mesh: &Mesh, file_name: &str, texture_file: Option<&str>) -> Result<()> {
+    let obj_path = Path::new(file_name);
+    let mtl_path = obj_path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("invalid obj file name {:?}", file_name))?;
+
+    let mut mtl = BufWriter::new(
+        File::create(&mtl_path).with_context(|| format!("cannot create {:?}", mtl_path))?,
+    );
+    write_mtl(&mut mtl, texture_file)?;
+    mtl.flush()?;
+
+    let mut obj = BufWriter::new(
+        File::create(obj_path).with_context(|| format!("cannot create {:?}", obj_path))?,
+    );
+    write_obj(mesh, &mut obj, Some(mtl_name))?;
+    obj.flush()?;
+    Ok(())
+}
+
+// one face corner: position, uv and normal indices (0-based), or for flat shaded faces without
+// normals the index of the face, so that its corners are not shared with neighbouring faces
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Corner {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+    flat_face: Option<usize>,
+}
+
+// parse a 1-based (or negative, counting back from the end) obj index
+fn parse_index(token: &str, count: usize, kind: &str) -> Result<usize> {
+    let index: i64 = token
+        .parse()
+        .with_context(|| format!("invalid {} index {:?}", kind, token))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    ensure!(
+        index != 0 && resolved >= 0 && (resolved as usize) < count,
+        "{} index {} out of range (have {})",
+        kind,
+        index,
+        count
+    );
+    Ok(resolved as usize)
+}
+
+fn parse_floats<const N: usize>(tokens: &[&str], min: usize) -> Result<[f32; N]> {
+    ensure!(
+        tokens.len() >= min,
+        "expected at least {} numbers, found {}",
+        min,
+        tokens.len()
+    );
+    let mut values = [0.0; N];
+    for (value, token) in values.iter_mut().zip(tokens) {
+        *value = token
+            .parse()
+            .with_context(|| format!("invalid number {:?}", token))?;
+    }
+    Ok(values)
+}
+
+// the raw obj statements, before the corners are merged into mesh vertices
+#[derive(Default)]
+struct ObjData {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    normals: Vec<[f32; 3]>,
+    faces: Vec<Vec<Corner>>,
+    flat: bool,
+}
+
+impl ObjData {
+    fn parse_line(&mut self, keyword: &str, args: &[&str]) -> Result<()> {
+        match keyword {
+            "v" => {
+                let v: [f32; 6] = parse_floats(args, 3)?;
+                self.positions.push([v[0], v[1], v[2]]);
+                if args.len() >= 6 {
+                    self.colors.push([v[3], v[4], v[5]]);
+                }
+            }
+            "vt" => {
+                let t: [f32; 2] = parse_floats(args, 1)?;
+                self.uvs.push([t[0], 1.0 - t[1]]);
+            }
+            "vn" => self.normals.push(parse_floats(args, 3)?),
+            "s" => self.flat = matches!(args.first(), Some(&"off") | Some(&"0")),
+            "f" => {
+                ensure!(args.len() >= 3, "a face needs at least 3 vertices");
+                let mut face = Vec::with_capacity(args.len());
+                for arg in args {
+                    let mut parts = arg.split('/');
+                    let position =
+                        parse_index(parts.next().unwrap_or(""), self.positions.len(), "vertex")?;
+                    let uv = match parts.next() {
+                        Some(t) if !t.is_empty() => {
+                            Some(parse_index(t, self.uvs.len(), "texture")?)
+                        }
+                        _ => None,
+                    };
+                    let normal = match parts.next() {
+                        Some(n) if !n.is_empty() => {
+                            Some(parse_index(n, self.normals.len(), "normal")?)
+                        }
+                        _ => None,
+                    };
+                    let flat_face = if normal.is_none() && self.flat {
+                        Some(self.faces.len())
+                    } else {
+                        None
+                    };
+                    face.push(Corner {
+                        position,
+                        uv,
+                        normal,
+                        flat_face,
+                    });
+                }
+                self.faces.push(face);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// read a wavefront obj into an indexed mesh. polygons are triangulated as fans, vertex colors
+// written as "v x y z r g b" are kept, and texture coordinates are flipped to wgpu's convention
+// (v = 0 at the top of the image). missing normals are computed: averaged over the adjacent
+// faces, or per face after "s off". materials, groups, lines and points are ignored.
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<Mesh> {
+    let mut data = ObjData::default();
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if let Some((keyword, args)) = tokens.split_first() {
+            data.parse_line(keyword, args)
+                .with_context(|| format!("line {}: {:?}", line_number + 1, line))?;
+        }
+    }
+    let ObjData {
+        positions,
+        mut colors,
+        uvs,
+        normals,
+        faces,
+        ..
+    } = data;
+    ensure!(!faces.is_empty(), "no faces found");
+    if colors.len() != positions.len() {
+        colors.clear();
+    }
+
+    // smooth normals for corners without one, averaged over the faces around each position
+    let triangles: Vec<u32> = faces
+        .iter()
+        .flat_map(|face| {
+            (1..face.len() - 1)
+                .flat_map(move |k| [face[0], face[k], face[k + 1]].map(|c| c.position as u32))
+        })
+        .collect();
+    let position_normals = smooth_normals(&positions, &triangles, false);
+
+    let mut mesh = Mesh::default();
+    let mut indices: Vec<u32> = Vec::with_capacity(triangles.len());
+    let mut corner_index: HashMap<Corner, u32> = HashMap::new();
+    for face in &faces {
+        // newell's method gives the normal of any planar polygon
+        let mut face_normal = Vector3::new(0.0f32, 0.0, 0.0);
+        for (k, c) in face.iter().enumerate() {
+            let p = Vector3::from(positions[c.position]);
+            let q = Vector3::from(positions[face[(k + 1) % face.len()].position]);
+            face_normal += p.cross(q);
+        }
+        let face_normal: [f32; 3] = if face_normal.magnitude2() > 0.0 {
+            face_normal.normalize().into()
+        } else {
+            [0.0, 1.0, 0.0]
+        };
+
+        let face_indices: Vec<u32> = face
+            .iter()
+            .map(|&c| {
+                *corner_index.entry(c).or_insert_with(|| {
+                    mesh.positions.push(positions[c.position]);
+                    if !colors.is_empty() {
+                        mesh.colors.push(colors[c.position]);
+                    }
+                    mesh.normals.push(match (c.normal, c.flat_face) {
+                        (Some(n), _) => normals[n],
+                        (None, Some(_)) => face_normal,
+                        (None, None) => position_normals[c.position],
+                    });
+                    mesh.uvs.push(c.uv.map_or([0.0, 0.0], |t| uvs[t]));
+                    mesh.positions.len() as u32 - 1
+                })
+            })
+            .collect();
+        for k in 1..face_indices.len() - 1 {
+            indices.extend_from_slice(&[face_indices[0], face_indices[k], face_indices[k + 1]]);
+        }
+    }
+    if uvs.is_empty() {
+        mesh.uvs.clear();
+    }
+    mesh.indices = Some(indices);
+    Ok(mesh)
+}
+
+pub fn load_obj(file_name: &str) -> Result<Mesh> {
+    let file = File::open(file_name).with_context(|| format!("cannot open {}", file_name))?;
+    parse_obj(BufReader::new(file)).with_context(|| format!("cannot read {}", file_name))
+}
@@ -0,0 +1,110 @@
+use anyhow::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::mesh::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+// colormap colors are stored as 8-bit channels, the way most ply readers expect them
+fn color_byte(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+// write a mesh as a ply file with positions and, when the mesh has them, normals (nx, ny, nz),
+// vertex colors (red, green, blue) and texture coordinates (s, t)
+pub fn write_ply<W: Write>(mesh: &Mesh, w: &mut W, format: PlyFormat) -> Result<()> {
+    let has_colors = mesh.colors.len() == mesh.positions.len();
+    let has_normals = mesh.normals.len() == mesh.positions.len();
+    let has_uvs = mesh.uvs.len() == mesh.positions.len();
+    let triangles = mesh.triangles();
+
+    writeln!(w, "ply")?;
+    match format {
+        PlyFormat::Ascii => writeln!(w, "format ascii 1.0")?,
+        PlyFormat::BinaryLittleEndian => writeln!(w, "format binary_little_endian 1.0")?,
+    }
+    writeln!(w, "comment hello_world mesh")?;
+    writeln!(w, "element vertex {}", mesh.num_vertices())?;
+    writeln!(w, "property float x\nproperty float y\nproperty float z")?;
+    if has_normals {
+        writeln!(w, "property float nx\nproperty float ny\nproperty float nz")?;
+    }
+    if has_colors {
+        writeln!(
+            w,
+            "property uchar red\nproperty uchar green\nproperty uchar blue"
+        )?;
+    }
+    if has_uvs {
+        writeln!(w, "property float s\nproperty float t")?;
+    }
+    writeln!(w, "element face {}", triangles.len())?;
+    writeln!(w, "property list uchar uint vertex_indices")?;
+    writeln!(w, "end_header")?;
+
+    for i in 0..mesh.num_vertices() {
+        let mut floats: Vec<f32> = mesh.positions[i].to_vec();
+        if has_normals {
+            floats.extend_from_slice(&mesh.normals[i]);
+        }
+        let color: Vec<u8> = if has_colors {
+            mesh.colors[i].map(color_byte).to_vec()
+        } else {
+            Vec::new()
+        };
+        // ply puts t = 0 at the bottom of the image, wgpu at the top
+        let uv: Vec<f32> = if has_uvs {
+            vec![mesh.uvs[i][0], 1.0 - mesh.uvs[i][1]]
+        } else {
+            Vec::new()
+        };
+
+        match format {
+            PlyFormat::Ascii => {
+                let fields: Vec<String> = floats
+                    .iter()
+                    .map(|f| f.to_string())
+                    .chain(color.iter().map(|c| c.to_string()))
+                    .chain(uv.iter().map(|f| f.to_string()))
+                    .collect();
+                writeln!(w, "{}", fields.join(" "))?;
+            }
+            PlyFormat::BinaryLittleEndian => {
+                for f in &floats {
+                    w.write_all(&f.to_le_bytes())?;
+                }
+                w.write_all(&color)?;
+                for f in &uv {
+                    w.write_all(&f.to_le_bytes())?;
+                }
+            }
+        }
+    }
+
+    for [a, b, c] in triangles {
+        match format {
+            PlyFormat::Ascii => writeln!(w, "3 {} {} {}", a, b, c)?,
+            PlyFormat::BinaryLittleEndian => {
+                w.write_all(&[3])?;
+                for i in [a, b, c] {
+                    w.write_all(&i.to_le_bytes())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn save_ply(mesh: &Mesh, file_name: &str, format: PlyFormat) -> Result<()> {
+    let mut w = BufWriter::new(
+        File::create(file_name).with_context(|| format!("cannot create {}", file_name))?,
+    );
+    write_ply(mesh, &mut w, format)?;
+    w.flush()?;
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use anyhow::{bail, Context, Result};
+
+use crate::colorbar::glyph;
+use crate::colormap::Colormap;
+use crate::texture_data::colormap_image;
+
+// textures generated on the cpu instead of loaded from a file, so that tests and demos need no
+// assets; all of them tile when sampled with the repeat address mode. see
+// Texture::create_procedural_texture in texture_data.rs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Procedural {
+    // cells x cells squares, alternating white and dark gray
+    Checkerboard { cells: u32 },
+    // a grid of cells x cells with u in red and v in green, labelled with the column and row of
+    // each cell, so that stretched, flipped or rotated uv mappings are easy to spot
+    UvGrid { cells: u32 },
+    // gray fractal perlin noise with features the size of 1 / cells, adding octaves of twice the
+    // frequency and half the amplitude
+    Noise { cells: u32, octaves: u32, seed: u32 },
+    // a colormap from u = 0 to u = 1
+    Gradient(Colormap),
+}
+
+const NAMES: &str = "checker[:cells], uv[:cells], noise[:cells[:octaves[:seed]]] or \
+                     gradient[:colormap]";
+
+// the most cells across a checkerboard, uv grid or noise texture; from_name rejects more and
+// image clamps them
+pub const MAX_CELLS: u32 = 256;
+
+// the most octaves of noise; later ones add under 1 / 65536 of the amplitude, so from_name
+// rejects more and fractal_noise clamps them
+pub const MAX_OCTAVES: u32 = 16;
+
+// the base names of the procedural textures, before any ":" options
+const BASE_NAMES: [&str; 4] = ["checker", "uv", "noise", "gradient"];
+
+// whether from_name takes name as a procedural texture rather than, say, an image file name;
+// true for "uv" and "noise:8", and also for names like "uv:x" that from_name then rejects
+pub fn is_procedural_name(name: &str) -> bool {
+    let base = name.split(':').next().unwrap_or(name);
+    BASE_NAMES.contains(&base)
+}
+
+impl Procedural {
+    // a procedural texture by name with ":" separated numbers, e.g. "checker:8", "uv",
+    // "noise:4:5:7" or "gradient:viridis_r"
+    pub fn from_name(name: &str) -> Result<Self> {
+        let (base, options) = name.split_once(':').unwrap_or((name, ""));
+        if base == "gradient" {
+            let colormap = if options.is_empty() { "jet" } else { options };
+            return Ok(Self::Gradient(Colormap::from_name(colormap)?));
+        }
+        let numbers = options
+            .split(':')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u32>().ok().filter(|&n| n > 0))
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| format!("invalid options in '{}', expected {}", name, NAMES))?;
+        let number = |i: usize, default: u32| numbers.get(i).copied().unwrap_or(default);
+        let (procedural, count) = match base {
+            "checker" => (
+                Self::Checkerboard {
+                    cells: number(0, 8),
+                },
+                1,
+            ),
+            "uv" => (
+                Self::UvGrid {
+                    cells: number(0, 8),
+                },
+                1,
+            ),
+            "noise" => {
+                let (cells, octaves, seed) = (number(0, 4), number(1, 5), number(2, 1));
+                (
+                    Self::Noise {
+                        cells,
+                        octaves,
+                        seed,
+                    },
+                    3,
+                )
+            }
+            _ => bail!("unknown procedural texture '{}', expected {}", name, NAMES),
+        };
+        if numbers.len() > count {
+            bail!("too many options in '{}', expected {}", name, NAMES);
+        }
+        if matches!(numbers.first(), Some(&cells) if cells > MAX_CELLS) {
+            bail!("too many cells in '{}', at most {} fit", name, MAX_CELLS);
+        }
+        if matches!(procedural, Self::Noise { octaves, .. } if octaves > MAX_OCTAVES) {
+            bail!(
+                "too many octaves in '{}', at most {} are used",
+                name,
+                MAX_OCTAVES
+            );
+        }
+        Ok(procedural)
+    }
+
+    // the texture as a width x height srgb image, with u along x and v along y
+    pub fn image(&self, width: u32, height: u32) -> image::RgbaImage {
+        let (w, h) = (width.max(1), height.max(1));
+        match self {
+            Self::Checkerboard { cells } => image::RgbaImage::from_fn(w, h, |x, y| {
+                let cells = (*cells).clamp(1, MAX_CELLS);
+                let (i, j) = (x * cells / w, y * cells / h);
+                let v = if (i + j) & 1 == 0 { 230 } else { 50 };
+                image::Rgba([v, v, v, 255])
+            }),
+            Self::UvGrid { cells } => uv_grid_image(w, h, *cells),
+            Self::Noise {
+                cells,
+                octaves,
+                seed,
+            } => image::RgbaImage::from_fn(w, h, |x, y| {
+                let (u, v) = ((x as f32 + 0.5) / w as f32, (y as f32 + 0.5) / h as f32);
+                let n = fractal_noise(u, v, *cells, *octaves, *seed);
+                let v = ((0.5 + 0.5 * n).clamp(0.0, 1.0) * 255.0).round() as u8;
+                image::Rgba([v, v, v, 255])
+            }),
+            Self::Gradient(colormap) => {
+                let row = colormap_image(colormap, w);
+                image::RgbaImage::from_fn(w, h, |x, _| *row.get_pixel(x, 0))
+            }
+        }
+    }
+}
+
+fn uv_grid_image(width: u32, height: u32, cells: u32) -> image::RgbaImage {
+    let cells = cells.clamp(1, MAX_CELLS.min(width).min(height));
+    let mut img = image::RgbaImage::from_fn(width, height, |x, y| {
+        let (u, v) = (
+            (x as f32 + 0.5) / width as f32,
+            (y as f32 + 0.5) / height as f32,
+        );
+        // alternate cells a little darker, so that the grid reads even where u and v are small
+        let shade = if (x * cells / width + y * cells / height) & 1 == 0 {
+            1.0
+        } else {
+            0.8
+        };
+        let [r, g] = [u, v].map(|c| ((0.15 + 0.85 * c) * shade * 255.0).round() as u8);
+        image::Rgba([r, g, (64.0 * shade) as u8, 255])
+    });
+
+    // the cell labels, "column row" counted from u = 0 and v = 0, in the colorbar font as large
+    // as fits a third of the cell
+    let (cell_w, cell_h) = (width / cells, height / cells);
+    let widest = format!("{} {}", cells - 1, cells - 1).len() as u32 * 6;
+    let scale = (cell_w / 3 / widest).min(cell_h / 3 / 7).max(1);
+    for j in 0..cells {
+        for i in 0..cells {
+            let text = format!("{} {}", i, j);
+            let (x0, y0) = (
+                i * width / cells + scale * 2,
+                j * height / cells + scale * 2,
+            );
+            for (k, c) in text.chars().enumerate() {
+                let rows = glyph(c).unwrap_or([0; 7]);
+                for (row, bits) in rows.iter().enumerate() {
+                    for col in 0..5 {
+                        if bits & (0b10000 >> col) == 0 {
+                            continue;
+                        }
+                        let px = x0 + (k as u32 * 6 + col) * scale;
+                        for (x, y) in square(px, y0 + row as u32 * scale, scale) {
+                            if x < width && y < height {
+                                img.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // grid lines at the cell borders, including the texture edges so that seams show
+    let line = (width.min(height) / 256).max(1);
+    for (x, y, pixel) in img.enumerate_pixels_mut() {
+        let near = |p: u32, size: u32| {
+            let d = (p * cells) % size;
+            d < line * cells || d >= size.saturating_sub(line * cells)
+        };
+        if near(x, width) || near(y, height) {
+            *pixel = image::Rgba([255, 255, 255, 255]);
+        }
+    }
+    img
+}
+
+fn square(x: u32, y: u32, size: u32) -> impl Iterator<Item = (u32, u32)> {
+    (0..size * size).map(move |k| (x + k % size, y + k / size))
+}
+
+// the sum of octaves of perlin noise, at most MAX_OCTAVES, scaled back to about [-1, 1]; the
+// lattice of every octave wraps at u, v = 1, so the noise tiles
+pub fn fractal_noise(u: f32, v: f32, cells: u32, octaves: u32, seed: u32) -> f32 {
+    let (mut sum, mut total, mut amplitude, mut period) = (0.0, 0.0, 1.0, cells.max(1));
+    for octave in 0..octaves.clamp(1, MAX_OCTAVES) {
+        let n = perlin(
+            u * period as f32,
+            v * period as f32,
+            period,
+            seed.wrapping_add(octave),
+        );
+        sum += amplitude * n;
+        total += amplitude;
+        amplitude *= 0.5;
+        period = period.saturating_mul(2);
+    }
+    sum / total
+}
+
+// 2d gradient noise at (x, y), repeating every period lattice cells; in about [-1, 1], and 0 at
+// every lattice point
+pub fn perlin(x: f32, y: f32, period: u32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    let period = period.max(1) as i64;
+    let corner = |dx: i64, dy: i64| {
+        let (cx, cy) = ((ix + dx).rem_euclid(period), (iy + dy).rem_euclid(period));
+        let turns = hash(cx as u32, cy as u32, seed) as f32 / u32::MAX as f32;
+        let angle = turns * std::f32::consts::TAU;
+        let (px, py) = (fx - dx as f32, fy - dy as f32);
+        // scaled so that the extremes, halfway between lattice points, are near +-1
+        std::f32::consts::SQRT_2 * (angle.cos() * px + angle.sin() * py)
+    };
+    let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let (sx, sy) = (fade(fx), fade(fy));
+    let top = corner(0, 0) + sx * (corner(1, 0) - corner(0, 0));
+    let bottom = corner(0, 1) + sx * (corner(1, 1) - corner(0, 1));
+    top + sy * (bottom - top)
+}
+
+// a well mixed 32 bit hash of a lattice point
+fn hash(x: u32, y: u32, seed: u32) -> u32 {
+    let mut h =
+        x.wrapping_mul(0x8da6_b343) ^ y.wrapping_mul(0xd816_3841) ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,71 @@
+use anyhow::*;
+use cgmath::{InnerSpace, Vector3};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::mesh::Mesh;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+// write the triangles of a mesh as stl. stl has no shared vertices, colors or uvs; each facet
+// carries its own normal, computed from the winding. use Mesh::thicken first to turn an open
+// surface into a printable solid.
+pub fn write_stl<W: Write>(mesh: &Mesh, w: &mut W, format: StlFormat) -> Result<()> {
+    let triangles = mesh.triangles();
+    let facets = triangles.iter().map(|tri| {
+        let p = tri.map(|i| mesh.positions[i as usize]);
+        let [a, b, c] = p.map(Vector3::from);
+        let n = (b - a).cross(c - a);
+        let n = if n.magnitude2() > 0.0 {
+            n.normalize()
+        } else {
+            n
+        };
+        (n, p)
+    });
+
+    match format {
+        StlFormat::Ascii => {
+            writeln!(w, "solid hello_world")?;
+            for (n, p) in facets {
+                writeln!(w, "  facet normal {} {} {}", n.x, n.y, n.z)?;
+                writeln!(w, "    outer loop")?;
+                for v in p {
+                    writeln!(w, "      vertex {} {} {}", v[0], v[1], v[2])?;
+                }
+                writeln!(w, "    endloop")?;
+                writeln!(w, "  endfacet")?;
+            }
+            writeln!(w, "endsolid hello_world")?;
+        }
+        StlFormat::Binary => {
+            // the 80-byte header must not start with "solid", or readers take the file for ascii
+            let mut header = [0u8; 80];
+            let text = b"binary stl from hello_world";
+            header[..text.len()].copy_from_slice(text);
+            w.write_all(&header)?;
+            w.write_all(&(triangles.len() as u32).to_le_bytes())?;
+            for (n, p) in facets {
+                for f in [n.x, n.y, n.z].iter().chain(p.iter().flatten()) {
+                    w.write_all(&f.to_le_bytes())?;
+                }
+                // attribute byte count, unused
+                w.write_all(&0u16.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn save_stl(mesh: &Mesh, file_name: &str, format: StlFormat) -> Result<()> {
+    let mut w = BufWriter::new(
+        File::create(file_name).with_context(|| format!("cannot create {}", file_name))?,
+    );
+    write_stl(mesh, &mut w, format)?;
+    w.flush()?;
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use cgmath::*;
+use crate::colormap::Colormap;
+use crate::mesh::{grid_indices, smooth_normals, Mesh, NormalMode};
+
+fn normalize_point(pt:[f32;3], xmin:f32, xmax:f32, ymin:f32, ymax:f32, zmin:f32, zmax:f32, scale:f32) -> [f32;3] { 
+    let px = scale * (-1.0 + 2.0 * (pt[0] - xmin) / (xmax - xmin)); 
+    let py = scale * (-1.0 + 2.0 * (pt[1] - ymin) / (ymax - ymin)); 
+    let pz = scale * (-1.0 + 2.0 * (pt[2] - zmin) / (zmax - zmin)); 
+    [px, py, pz] 
+}
+
+// where a surface function is sampled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Domain {
+    // y = f(x, z) over [xmin, xmax] x [zmin, zmax]
+    Simple { x: [f32; 2], z: [f32; 2] },
+    // f(u, v) over [umin, umax] x [vmin, vmax]; the points are normalized from the box
+    // [xmin, xmax] x [zmin, zmax]
+    Parametric { u: [f32; 2], v: [f32; 2], x: [f32; 2], z: [f32; 2] },
+}
+
+impl Domain {
+    // the ranges f is sampled over, along the first and second argument
+    pub fn sampled(&self) -> [[f32; 2]; 2] {
+        match *self {
+            Domain::Simple { x, z } => [x, z],
+            Domain::Parametric { u, v, .. } => [u, v],
+        }
+    }
+
+    // the x and z ranges the points are normalized from
+    pub fn bounds(&self) -> [[f32; 2]; 2] {
+        match *self {
+            Domain::Simple { x, z } | Domain::Parametric { x, z, .. } => [x, z],
+        }
+    }
+}
+
+// samples f on an nu x nv grid over the domain and normalizes the points into the scaled box;
+// also returns the normalized y range used for the colormap and the per-axis scale factors of
+// the normalization
+fn sample_grid(f: &dyn Fn(f32, f32) -> [f32; 3], domain: &Domain, [nu, nv]: [usize; 2], scale: f32,
+scaley: f32) -> (Vec<Vec<[f32; 3]>>, f32, f32, [f32; 3]) { 
+    let [[umin, umax], [vmin, vmax]] = domain.sampled();
+    let [[xmin, xmax], [zmin, zmax]] = domain.bounds();
+    let du = (umax-umin)/(nu as f32-1.0); 
+    let dv = (vmax-vmin)/(nv as f32-1.0); 
+    let mut ymin1: f32 = 0.0; 
+    let mut ymax1: f32 = 0.0; 
+
+    let mut pts:Vec<Vec<[f32; 3]>> = vec![vec![Default::default(); nv]; nu]; 
+    for i in 0..nu { 
+        let u = umin + i as f32 * du; 
+        let mut pt1:Vec<[f32; 3]> = Vec::with_capacity(nv); 
+        for j in 0..nv { 
+            let v = vmin + j as f32 * dv; 
+            let pt = f(u, v); 
+            pt1.push(pt); 
+            ymin1 = if pt[1] < ymin1 { pt[1] } else { ymin1 }; 
+            ymax1 = if pt[1] > ymax1 { pt[1] } else { ymax1 }; 
+        } 
+        pts[i] = pt1; 
+    } 
+
+    let ymin = ymin1 - scaley * (ymax1 - ymin1); 
+    let ymax = ymax1 + scaley * (ymax1 - ymin1); 
+    for i in 0..nu { 
+        for j in 0..nv { 
+            pts[i][j] = normalize_point(pts[i][j], xmin, xmax, ymin, ymax, zmin, zmax, scale); 
+        } 
+    } 
+
+    let cmin = normalize_point([0.0, ymin1, 0.0], xmin, xmax, ymin, ymax, zmin, zmax, scale)[1]; 
+    let cmax = normalize_point([0.0, ymax1, 0.0], xmin, xmax, ymin, ymax, zmin, zmax, scale)[1]; 
+    let axis = [2.0 * scale / (xmax - xmin), 2.0 * scale / (ymax - ymin), 2.0 * scale / (zmax - zmin)]; 
+    (pts, cmin, cmax, axis) 
+}
+
+// per-point normals of a sampled grid for the smooth modes. the analytic normals come from the
+// partial derivatives of f, estimated by central differences and taken through the same scaling
+// as the points; where they vanish (e.g. at a pole) the area-weighted normal is used instead.
+fn grid_normals(f: &dyn Fn(f32, f32) -> [f32; 3], pts: &[Vec<[f32; 3]>], axis: [f32; 3],
+corners: [(usize, usize); 4], normal_mode: NormalMode, domain: &Domain) -> Vec<[f32; 3]> {
+    let nu = pts.len();
+    let nv = pts[0].len();
+    let positions: Vec<[f32; 3]> = pts.iter().flatten().copied().collect();
+    let indices = grid_indices(nu, nv, corners);
+    let mut normals = smooth_normals(&positions, &indices, normal_mode == NormalMode::AngleWeighted);
+    if normal_mode != NormalMode::Analytic {
+        return normals;
+    }
+
+    let [[umin, umax], [vmin, vmax]] = domain.sampled();
+    let du = (umax - umin) / (nu as f32 - 1.0);
+    let dv = (vmax - vmin) / (nv as f32 - 1.0);
+    let (hu, hv) = (du * 1e-2, dv * 1e-2);
+    let scaled = |p: [f32; 3]| Vector3::new(p[0] * axis[0], p[1] * axis[1], p[2] * axis[2]);
+    for i in 0..nu {
+        for j in 0..nv {
+            let u = umin + i as f32 * du;
+            let v = vmin + j as f32 * dv;
+            let tu = (scaled(f(u + hu, v)) - scaled(f(u - hu, v))) / (2.0 * hu);
+            let tv = (scaled(f(u, v + hv)) - scaled(f(u, v - hv))) / (2.0 * hv);
+            // orient the normal like the quad diagonals (c2 - c0) x (c3 - c1) of the winding
+            let c = corners.map(|(di, dj)| tu * di as f32 + tv * dj as f32);
+            let n = (c[2] - c[0]).cross(c[3] - c[1]);
+            if n.magnitude2() > 0.0 && n.magnitude2().is_finite() {
+                normals[i * nv + j] = n.normalize().into();
+            }
+        }
+    }
+    normals
+}
+
+// texture coordinates of grid point (i, j): u and v normalized over the sampled domain
+fn grid_uv(i: usize, j: usize, nu: usize, nv: usize) -> [f32; 2] { 
+    [i as f32 / (nu as f32 - 1.0), j as f32 / (nv as f32 - 1.0)] 
+}
+
+fn create_quad(p0:[f32;3], p1:[f32;3], p2:[f32;3], p3:[f32;3], uvs:[[f32;2];4], ymin:f32, ymax:f32, colormap: &Colormap) -> 
+(Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<[f32; 2]>) {
+    // position
+    let mut position:Vec<[f32; 3]> = Vec::with_capacity(6); 
+    position.push(p0); 
+    position.push(p1); 
+    position.push(p2); 
+    position.push(p2); 
+    position.push(p3); 
+    position.push(p0);
+
+    // normal
+    let ca = Vector3::new(p2[0]-p0[0], p2[1]-p0[1], p2[2]-p0[2]); 
+    let db = Vector3::new(p3[0]-p1[0], p3[1]-p1[1], p3[2]-p1[2]); 
+    let cp = (ca.cross(db)).normalize(); 
+    let mut normal:Vec<[f32;3]> = Vec::with_capacity(6); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+    normal.push([cp[0], cp[1], cp[2]]); 
+
+    // color
+    let c0 = colormap.color_lerp(ymin, ymax, p0[1]); 
+    let c1 = colormap.color_lerp(ymin, ymax, p1[1]); 
+    let c2 = colormap.color_lerp(ymin, ymax, p2[1]); 
+    let c3 = colormap.color_lerp(ymin, ymax, p3[1]); 
+    let mut color:Vec<[f32;3]> = Vec::with_capacity(6); 
+    color.push(c0); 
+    color.push(c1); 
+    color.push(c2); 
+    color.push(c2); 
+    color.push(c3); 
+    color.push(c0); 
+
+    // uv
+    let mut uv:Vec<[f32;2]> = Vec::with_capacity(6); 
+    uv.push(uvs[0]); 
+    uv.push(uvs[1]); 
+    uv.push(uvs[2]); 
+    uv.push(uvs[2]); 
+    uv.push(uvs[3]); 
+    uv.push(uvs[0]); 
+    (position, normal, color, uv) 
+}
+
+pub fn simple_surface_data(f: &dyn Fn(f32, f32) -> [f32; 3], colormap: &Colormap, xmin:f32, xmax:f32, 
+zmin:f32, zmax:f32, nx:usize, nz: usize, scale: f32, scaley: f32, normal_mode: NormalMode) -> Mesh {
+    let domain = Domain::Simple { x: [xmin, xmax], z: [zmin, zmax] };
+    let (pts, cmin, cmax, axis) = sample_grid(f, &domain, [nx, nz], scale, scaley); 
+    let corners = [(0, 0), (0, 1), (1, 1), (1, 0)]; 
+    let smooth = if normal_mode == NormalMode::Flat { 
+        None 
+    } else { 
+        Some(grid_normals(f, &pts, axis, corners, normal_mode, &domain)) 
+    }; 
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity((4* (nx - 1)*(nz -1)) as usize); 
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity((4* (nx - 1)*(nz -1)) as usize); 
+    let mut colors: Vec<[f32; 3]> = Vec::with_capacity((4* (nx - 1)*(nz -1)) as usize); 
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity((4* (nx - 1)*(nz -1)) as usize); 
+    for i in 0..nx - 1 { 
+        for j in 0.. nz - 1 { 
+            let p0 = pts[i][j]; 
+            let p1 = pts[i][j+1]; 
+            let p2 = pts[i+1][j+1]; 
+            let p3 = pts[i+1][j]; 
+            let uv = corners.map(|(di, dj)| grid_uv(i + di, j + dj, nx, nz)); 
+            let ( mut pos, mut norm, mut col, mut uv) = 
+            create_quad(p0, p1, p2, p3, uv, cmin, cmax, colormap); 
+            
+            // positions
+            positions.append(&mut pos); 
+            // normals
+            if let Some(smooth) = &smooth { 
+                let [n0, n1, n2, n3] = corners.map(|(di, dj)| smooth[(i + di) * nz + j + dj]); 
+                norm = vec![n0, n1, n2, n2, n3, n0]; 
+            } 
+            normals.append(&mut norm); 
+            
+            // colors
+            colors.append(&mut col); 
+
+            // uvs
+            uvs.append(&mut uv); 
+        } 
+    } 
+    Mesh { 
+        positions, 
+        normals, 
+        colors, 
+        uvs, 
+        ..Default::default() 
+    } 
+}
+
+pub fn parametric_surface_data(f: &dyn Fn(f32, f32) -> [f32; 3], colormap: &Colormap, umin:f32, umax:f32, 
+vmin:f32, vmax:f32, nu:usize, nv: usize, xmin:f32, xmax:f32, zmin:f32, zmax:f32, scale:f32, scaley:f32, 
+normal_mode: NormalMode) -> Mesh { 
+    let domain =
+        Domain::Parametric { u: [umin, umax], v: [vmin, vmax], x: [xmin, xmax], z: [zmin, zmax] };
+    let (pts, cmin, cmax, axis) = sample_grid(f, &domain, [nu, nv], scale, scaley); 
+    let corners = [(0, 0), (1, 0), (1, 1), (0, 1)]; 
+    let smooth = if normal_mode == NormalMode::Flat { 
+        None 
+    } else { 
+        Some(grid_normals(f, &pts, axis, corners, normal_mode, &domain)) 
+    }; 
+
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity((4* (nu - 1)*(nv -1)) as usize); 
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity((4* (nu - 1)*(nv -1)) as usize); 
+    let mut colors: Vec<[f32; 3]> = Vec::with_capacity((4* (nu - 1)*(nv -1)) as usize); 
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity((4* (nu - 1)*(nv -1)) as usize); 
+
+    for i in 0..nu - 1 { 
+        for j in 0.. nv - 1 { 
+            let p0 = pts[i][j]; 
+            let p1 = pts[i+1][j]; 
+            let p2 = pts[i+1][j+1]; 
+            let p3 = pts[i][j+1]; 
+            let uv = corners.map(|(di, dj)| grid_uv(i + di, j + dj, nu, nv)); 
+            let ( mut pos, mut norm, mut col, mut uv) = 
+            create_quad(p0, p1, p2, p3, uv, cmin, cmax, colormap); 
+
+            // positions
+            positions.append(&mut pos); 
+            // normals
+            if let Some(smooth) = &smooth { 
+                let [n0, n1, n2, n3] = corners.map(|(di, dj)| smooth[(i + di) * nv + j + dj]); 
+                norm = vec![n0, n1, n2, n2, n3, n0]; 
+            } 
+            normals.append(&mut norm); 
+
+            // colors
+            colors.append(&mut col); 
+
+            // uvs
+            uvs.append(&mut uv); 
+        } 
+    } 
+
+    Mesh { 
+        positions, 
+        normals, 
+        colors, 
+        uvs, 
+        ..Default::default() 
+    } 
+}
+
+// shared-vertex version of a sampled grid: one vertex per grid point, colored by height and
+// textured over the domain.
+// corners gives the quad winding in (di, dj) offsets, as for mesh::grid_indices.
+fn indexed_grid(pts: &[Vec<[f32; 3]>], normals: Vec<[f32; 3]>, cmin: f32, cmax: f32, colormap: &Colormap,
+corners: [(usize, usize); 4]) -> Mesh {
+    let positions: Vec<[f32; 3]> = pts.iter().flatten().copied().collect();
+    let colors = positions.iter().map(|p| colormap.color_lerp(cmin, cmax, p[1])).collect();
+    let (nu, nv) = (pts.len(), pts[0].len());
+    let uvs = (0..nu * nv).map(|k| grid_uv(k / nv, k % nv, nu, nv)).collect();
+    Mesh {
+        positions,
+        normals,
+        colors,
+        uvs,
+        indices: Some(grid_indices(nu, nv, corners)),
+    }
+}
+
+// the indexed mesh of f sampled on a grid of resolution[0] x resolution[1] points over the
+// domain, normalized into a box of half-size scale with scaley of the y range added above and
+// below. the indexed surfaces share vertices between quads, so they cannot carry flat face
+// normals; NormalMode::Flat is treated as AreaWeighted
+pub fn surface_data_index(
+    f: &dyn Fn(f32, f32) -> [f32; 3],
+    colormap: &Colormap,
+    domain: &Domain,
+    resolution: [usize; 2],
+    scale: f32,
+    scaley: f32,
+    normal_mode: NormalMode,
+) -> Mesh {
+    let (pts, cmin, cmax, axis) = sample_grid(f, domain, resolution, scale, scaley);
+    // the same winding as simple_surface_data and parametric_surface_data
+    let corners = match domain {
+        Domain::Simple { .. } => [(0, 0), (0, 1), (1, 1), (1, 0)],
+        Domain::Parametric { .. } => [(0, 0), (1, 0), (1, 1), (0, 1)],
+    };
+    let normals = grid_normals(f, &pts, axis, corners, normal_mode, domain);
+    indexed_grid(&pts, normals, cmin, cmax, colormap, corners)
+}
@@ -0,0 +1,191 @@
+use anyhow::*;
+use std::f32::consts::PI;
+
+use crate::colormap::Colormap;
+use crate::math_func;
+use crate::mesh::{Mesh, NormalMode};
+use crate::surface_data;
+
+pub use crate::surface_data::Domain;
+
+// a built-in surface with the settings it is meant to be drawn with. copy an entry and change
+// its fields to draw it differently, e.g. at a higher resolution.
+#[derive(Clone, Copy)]
+pub struct Surface {
+    pub name: &'static str,
+    pub f: fn(f32, f32) -> [f32; 3],
+    pub domain: Domain,
+    // number of samples along x and z, or u and v
+    pub resolution: [usize; 2],
+    // half the size of the box the surface is normalized into
+    pub scale: f32,
+    // extra room above and below the y range, as a fraction of it
+    pub scaley: f32,
+    pub colormap: &'static str,
+}
+
+const SURFACES: &[Surface] = &[
+    Surface {
+        name: "sinc",
+        f: math_func::sinc,
+        domain: Domain::Simple {
+            x: [-8.0, 8.0],
+            z: [-8.0, 8.0],
+        },
+        resolution: [30, 30],
+        scale: 1.5,
+        scaley: 0.3,
+        colormap: "jet",
+    },
+    Surface {
+        name: "peaks",
+        f: math_func::peaks,
+        domain: Domain::Simple {
+            x: [-3.0, 3.0],
+            z: [-3.0, 3.0],
+        },
+        resolution: [51, 51],
+        scale: 1.5,
+        scaley: 0.0,
+        colormap: "jet",
+    },
+    Surface {
+        name: "klein_bottle",
+        f: math_func::klein_bottle,
+        domain: Domain::Parametric {
+            u: [0.0, PI],
+            v: [0.0, 2.0 * PI],
+            x: [-2.0, 2.0],
+            z: [-2.0, 2.0],
+        },
+        resolution: [70, 31],
+        scale: 1.5,
+        scaley: 0.0,
+        colormap: "cool",
+    },
+    Surface {
+        name: "wellenkugel",
+        f: math_func::wellenkugel,
+        domain: Domain::Parametric {
+            u: [0.0, 14.5],
+            v: [0.0, 2.0 * PI],
+            x: [-15.0, 15.0],
+            z: [-15.0, 15.0],
+        },
+        resolution: [70, 30],
+        scale: 1.5,
+        scaley: 0.3,
+        colormap: "hsv",
+    },
+    Surface {
+        name: "seashell",
+        f: math_func::seashell,
+        domain: Domain::Parametric {
+            u: [0.0, 6.0 * PI],
+            v: [0.0, 2.0 * PI],
+            x: [-4.0, 4.0],
+            z: [-4.0, 4.0],
+        },
+        resolution: [200, 40],
+        scale: 1.5,
+        scaley: 0.0,
+        colormap: "cool",
+    },
+    // the surface is singular at v = 0 and v = pi and along u = ±pi/2
+    Surface {
+        name: "sievert_enneper",
+        f: math_func::sievert_enneper,
+        domain: Domain::Parametric {
+            u: [-PI / 2.1, PI / 2.1],
+            v: [0.1, PI - 0.1],
+            x: [-1.5, 1.5],
+            z: [-1.5, 1.5],
+        },
+        resolution: [60, 60],
+        scale: 1.5,
+        scaley: 0.0,
+        colormap: "autumn",
+    },
+    Surface {
+        name: "breather",
+        f: math_func::breather,
+        domain: Domain::Parametric {
+            u: [-14.0, 14.0],
+            v: [-37.4, 37.4],
+            x: [-9.0, 9.0],
+            z: [-9.0, 9.0],
+        },
+        resolution: [150, 150],
+        scale: 1.5,
+        scaley: 0.4,
+        colormap: "spring",
+    },
+];
+
+pub fn surfaces() -> &'static [Surface] {
+    SURFACES
+}
+
+pub fn names() -> Vec<&'static str> {
+    SURFACES.iter().map(|s| s.name).collect()
+}
+
+pub fn find(name: &str) -> Result<&'static Surface> {
+    SURFACES.iter().find(|s| s.name == name).ok_or_else(|| {
+        anyhow!(
+            "unknown surface '{}', expected one of: {}",
+            name,
+            names().join(", ")
+        )
+    })
+}
+
+impl Surface {
+    pub fn mesh(&self, colormap: &Colormap, normal_mode: NormalMode) -> Mesh {
+        let [nu, nv] = self.resolution;
+        match self.domain {
+            Domain::Simple { x, z } => surface_data::simple_surface_data(
+                &self.f,
+                colormap,
+                x[0],
+                x[1],
+                z[0],
+                z[1],
+                nu,
+                nv,
+                self.scale,
+                self.scaley,
+                normal_mode,
+            ),
+            Domain::Parametric { u, v, x, z } => surface_data::parametric_surface_data(
+                &self.f,
+                colormap,
+                u[0],
+                u[1],
+                v[0],
+                v[1],
+                nu,
+                nv,
+                x[0],
+                x[1],
+                z[0],
+                z[1],
+                self.scale,
+                self.scaley,
+                normal_mode,
+            ),
+        }
+    }
+
+    pub fn mesh_index(&self, colormap: &Colormap, normal_mode: NormalMode) -> Mesh {
+        surface_data::surface_data_index(
+            &self.f,
+            colormap,
+            &self.domain,
+            self.resolution,
+            self.scale,
+            self.scaley,
+            normal_mode,
+        )
+    }
+}
@@ -0,0 +1,512 @@
+use image::io::Reader as ImageReader; 
+use anyhow::*; 
+
+use std::cell::RefCell;
+
+use crate::colormap::Colormap;
+use crate::procedural::Procedural;
+
+// width of the colormap textures; a shader looks up t in [0, 1] at
+// u = (t * (COLORMAP_WIDTH - 1) + 0.5) / COLORMAP_WIDTH, so that 0 and 1 hit the end texel centers
+pub const COLORMAP_WIDTH: u32 = 256;
+
+// a colormap sampled into a width x 1 image
+pub fn colormap_image(colormap: &Colormap, width: u32) -> image::RgbaImage {
+    image::RgbaImage::from_fn(width, 1, |x, _| {
+        let t = if width > 1 { x as f32 / (width - 1) as f32 } else { 0.5 };
+        let [r, g, b] = colormap.color(t).map(|c| (c * 255.0).round() as u8);
+        image::Rgba([r, g, b, 255])
+    })
+}
+
+// how the 8 and 16 bit values of an image are interpreted. color images are stored in srgb and
+// become linear when sampled; data such as normal maps, roughness or heights must be sampled as
+// stored, or they are darkened and bent. float images (hdr, exr, 32 bit tiff) are always linear.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+// how an image texture is sampled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextureOptions {
+    pub address_modes: [wgpu::AddressMode; 2],
+    // build the whole mip chain on the gpu and sample it trilinearly; without mipmaps the single
+    // level is filtered bilinearly, which aliases when the texture is minified
+    pub mipmaps: bool,
+    // maximum anisotropy, rounded down to a power of two up to 16; 1 turns it off. adapters
+    // without anisotropic filtering ignore it
+    pub anisotropy: u8,
+    pub color_space: ColorSpace,
+    // allow copying the texture out, e.g. with capture::capture_mip_level
+    pub readable: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self::new(wgpu::AddressMode::ClampToEdge, wgpu::AddressMode::ClampToEdge)
+    }
+}
+
+impl TextureOptions {
+    pub fn new(u_mode: wgpu::AddressMode, v_mode: wgpu::AddressMode) -> Self {
+        Self {
+            address_modes: [u_mode, v_mode],
+            mipmaps: true,
+            anisotropy: 1,
+            color_space: ColorSpace::Srgb,
+            readable: false,
+        }
+    }
+
+    pub fn anisotropy_clamp(&self) -> Option<std::num::NonZeroU8> {
+        let anisotropy = self.anisotropy.clamp(1, 16);
+        std::num::NonZeroU8::new(1 << (7 - anisotropy.leading_zeros())).filter(|a| a.get() > 1)
+    }
+
+    // the format of an 8 bit texture
+    pub fn rgba8_format(&self) -> wgpu::TextureFormat {
+        match self.color_space {
+            ColorSpace::Srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            ColorSpace::Linear => wgpu::TextureFormat::Rgba8Unorm,
+        }
+    }
+}
+
+// number of levels in a full mip chain, down to 1 x 1
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+// the bits of the half float nearest to x, as stored in a Rgba16Float texture
+pub fn f32_to_f16(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // infinity, or a quiet nan
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    // round to nearest, ties to even; a carry out of the mantissa correctly bumps the exponent,
+    // up to infinity
+    let round = |half: u32, rest: u32, halfway: u32| {
+        half + (rest > halfway || (rest == halfway && half & 1 == 1)) as u32
+    };
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        // a subnormal half, or zero
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let rest = mantissa & ((1 << shift) - 1);
+        sign | round(mantissa >> shift, rest, 1 << (shift - 1)) as u16
+    } else {
+        let half = ((exponent as u32) << 10) | (mantissa >> 13);
+        sign | round(half, mantissa & 0x1fff, 0x1000) as u16
+    }
+}
+
+pub struct Texture { 
+    pub texture: wgpu::Texture, 
+    pub view: wgpu::TextureView, 
+    pub sampler: wgpu::Sampler, 
+    pub format: wgpu::TextureFormat, 
+    // the sampler's u/v address modes, kept for export
+    pub address_modes: [wgpu::AddressMode; 2], 
+} 
+
+impl Texture {
+    pub fn create_texture_data(device:&wgpu::Device, queue: &wgpu::Queue, img_file: &str, 
+        u_mode:wgpu::AddressMode, v_mode:wgpu::AddressMode,) -> Result<Self> { 
+        let options = TextureOptions::new(u_mode, v_mode);
+        let mipmaps = MipmapGenerator::new(device);
+        Self::create_texture_with_options(device, queue, &mipmaps, img_file, &options)
+    } 
+
+    // a texture of an image file in any format the image crate reads; the format is guessed from
+    // the contents when the extension is missing or wrong
+    pub fn create_texture_with_options(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmaps: &MipmapGenerator,
+        img_file: &str,
+        options: &TextureOptions,
+    ) -> Result<Self> {
+        let img = ImageReader::open(img_file)
+            .with_context(|| format!("failed to open image '{}'", img_file))?
+            .with_guessed_format()
+            .with_context(|| format!("failed to read image '{}'", img_file))?
+            .decode()
+            .with_context(|| format!("failed to decode image '{}'", img_file))?;
+        Self::from_dynamic_image(device, queue, mipmaps, &img, options)
+            .with_context(|| format!("failed to load image '{}'", img_file))
+    }
+
+    // a texture of a decoded image. 8 bit images, color or gray, with or without alpha, become
+    // Rgba8UnormSrgb or Rgba8Unorm textures by the color space of the options; 16 bit and float
+    // images become Rgba16Float textures so that they keep their precision and range
+    pub fn from_dynamic_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmaps: &MipmapGenerator,
+        img: &image::DynamicImage,
+        options: &TextureOptions,
+    ) -> Result<Self> {
+        let (width, height) = (img.width(), img.height());
+        let max_size = device.limits().max_texture_dimension_2d;
+        ensure!(width > 0 && height > 0, "image is empty");
+        ensure!(
+            width <= max_size && height <= max_size,
+            "image is {} x {}, larger than the maximum texture size {}",
+            width,
+            height,
+            max_size
+        );
+
+        use image::DynamicImage::*;
+        let (float, srgb) = match img {
+            ImageLuma16(_) | ImageLumaA16(_) | ImageRgb16(_) | ImageRgba16(_) => {
+                (true, options.color_space == ColorSpace::Srgb)
+            }
+            ImageRgb32F(_) | ImageRgba32F(_) => (true, false),
+            _ => (false, false),
+        };
+        if !float {
+            return Ok(Self::from_image(device, queue, mipmaps, &img.to_rgba8(), options));
+        }
+
+        // a 16 bit srgb image is converted to linear here, as there is no srgb float format
+        let mut rgba = img.to_rgba32f();
+        if srgb {
+            for pixel in rgba.pixels_mut() {
+                let [r, g, b] = crate::colormap::srgb_to_linear([pixel[0], pixel[1], pixel[2]]);
+                pixel.0 = [r, g, b, pixel[3]];
+            }
+        }
+        let data: Vec<u8> = rgba
+            .as_raw()
+            .iter()
+            .flat_map(|&c| f32_to_f16(c).to_le_bytes())
+            .collect();
+        let format = wgpu::TextureFormat::Rgba16Float;
+        Ok(Self::upload(device, queue, mipmaps, &data, format, [width, height], options))
+    }
+
+    // an 8 bit texture of an image, srgb or linear by the options
+    pub fn from_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmaps: &MipmapGenerator,
+        rgba: &image::RgbaImage,
+        options: &TextureOptions,
+    ) -> Self {
+        let (width, height) = rgba.dimensions();
+        let format = options.rgba8_format();
+        Self::upload(device, queue, mipmaps, rgba.as_raw(), format, [width, height], options)
+    }
+
+    // a texture with its mip chain and sampler, of width x height tightly packed rgba texels of
+    // a 4 channel format
+    fn upload(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmaps: &MipmapGenerator,
+        data: &[u8],
+        format: wgpu::TextureFormat,
+        dimensions: [u32; 2],
+        options: &TextureOptions,
+    ) -> Self {
+        let size = wgpu::Extent3d {
+            width: dimensions[0],
+            height: dimensions[1],
+            depth_or_array_layers: 1,
+        };
+        let texel_size = format.describe().block_size as u32;
+        let mip_level_count = if options.mipmaps {
+            mip_level_count(size.width, size.height)
+        } else {
+            1
+        };
+
+        // the levels below the first are rendered from the one above into scratch textures and
+        // copied in
+        let usage = if options.readable {
+            wgpu::TextureUsages::COPY_SRC
+        } else {
+            wgpu::TextureUsages::empty()
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Image Texture"),
+            size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: usage | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        });
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            data,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(texel_size * dimensions[0]),
+                rows_per_image: std::num::NonZeroU32::new(dimensions[1]),
+            },
+            size,
+        );
+        mipmaps.generate(device, queue, &texture, format, size, mip_level_count);
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let [u_mode, v_mode] = options.address_modes;
+        let mipmap_filter = match options.mipmaps {
+            true => wgpu::FilterMode::Linear,
+            false => wgpu::FilterMode::Nearest,
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: u_mode,
+            address_mode_v: v_mode,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter,
+            anisotropy_clamp: options.anisotropy_clamp(),
+            ..Default::default()
+        });
+
+        Self { texture, view, sampler, format, address_modes: options.address_modes }
+    }
+
+    // a size x size texture generated on the cpu, e.g. a uv grid for checking a mesh's texture
+    // coordinates without an image file
+    pub fn create_procedural_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mipmaps: &MipmapGenerator,
+        procedural: &Procedural,
+        size: u32,
+        options: &TextureOptions,
+    ) -> Self {
+        Self::from_image(device, queue, mipmaps, &procedural.image(size, size), options)
+    }
+
+    // a COLORMAP_WIDTH x 1 srgb texture of a colormap, for looking up a scalar value in a shader
+    // instead of baking colors into the vertices
+    pub fn create_colormap_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        colormap: &Colormap,
+    ) -> Self {
+        let size = wgpu::Extent3d {
+            width: COLORMAP_WIDTH,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Colormap Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let address_mode = wgpu::AddressMode::ClampToEdge;
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let colormap_texture = Self {
+            texture,
+            view,
+            sampler,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            address_modes: [address_mode; 2],
+        };
+        colormap_texture.write_colormap(queue, colormap);
+        colormap_texture
+    }
+
+    // replace the colors of a colormap texture; bind groups using it stay valid
+    pub fn write_colormap(&self, queue: &wgpu::Queue, colormap: &Colormap) {
+        let img = colormap_image(colormap, COLORMAP_WIDTH);
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            &img,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: std::num::NonZeroU32::new(4 * COLORMAP_WIDTH),
+                rows_per_image: std::num::NonZeroU32::new(1),
+            },
+            wgpu::Extent3d {
+                width: COLORMAP_WIDTH,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+// renders the mip levels of textures; its shader, sampler and pipelines (one per texture format)
+// are made once per device, see InitWgpu::mipmaps
+pub struct MipmapGenerator {
+    shader: wgpu::ShaderModule,
+    sampler: wgpu::Sampler,
+    pipelines: RefCell<Vec<(wgpu::TextureFormat, wgpu::RenderPipeline)>>,
+}
+
+impl MipmapGenerator {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mipmap Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("mipmap.wgsl").into()),
+        });
+        // always read the first level of the source
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Mipmap Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            lod_max_clamp: 0.0,
+            ..Default::default()
+        });
+        Self { shader, sampler, pipelines: RefCell::new(Vec::new()) }
+    }
+
+    fn pipeline_index(&self, device: &wgpu::Device, format: wgpu::TextureFormat) -> usize {
+        let mut pipelines = self.pipelines.borrow_mut();
+        if let Some(i) = pipelines.iter().position(|(f, _)| *f == format) {
+            return i;
+        }
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mipmap Pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &self.shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shader,
+                entry_point: "fs_main",
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        pipelines.push((format, pipeline));
+        pipelines.len() - 1
+    }
+
+    // fill mip levels 1.. of a texture by rendering each level, bilinearly sampled, from the one
+    // above; an srgb format is averaged in linear space. the levels are rendered into scratch
+    // textures and copied into place, since some backends (gl) can only sample the first level
+    // of a texture and cannot render into the texture being sampled. the texture needs
+    // TextureUsages::COPY_DST.
+    pub fn generate(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        format: wgpu::TextureFormat,
+        size: wgpu::Extent3d,
+        mip_level_count: u32,
+    ) {
+        if mip_level_count <= 1 {
+            return;
+        }
+        let index = self.pipeline_index(device, format);
+        let pipelines = self.pipelines.borrow();
+        let pipeline = &pipelines[index].1;
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Mipmap Encoder"),
+        });
+        let mut source = texture.create_view(&wgpu::TextureViewDescriptor {
+            mip_level_count: std::num::NonZeroU32::new(1),
+            ..Default::default()
+        });
+        for level in 1..mip_level_count {
+            let level_size = wgpu::Extent3d {
+                width: (size.width >> level).max(1),
+                height: (size.height >> level).max(1),
+                depth_or_array_layers: 1,
+            };
+            let target = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Mip Level"),
+                size: level_size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+            });
+            let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+                label: None,
+            });
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Mipmap Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &target_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                });
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            }
+            encoder.copy_texture_to_texture(
+                target.as_image_copy(),
+                wgpu::ImageCopyTexture {
+                    mip_level: level,
+                    ..texture.as_image_copy()
+                },
+                level_size,
+            );
+            source = target_view;
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
@@ -0,0 +1,413 @@
+use cgmath::*;
+use std::cell::RefCell;
+use std::f32::consts::PI;
+use winit::window::Window;
+
+use crate::capture;
+use crate::texture_data::MipmapGenerator;
+
+pub struct InitWgpu {
+    pub surface: Option<wgpu::Surface>,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub config: wgpu::SurfaceConfiguration,
+    pub size: winit::dpi::PhysicalSize<u32>,
+    // render target used in place of the surface when running headless
+    pub offscreen: Option<wgpu::Texture>,
+    // png file the next frame is saved to; see request_capture
+    capture_file: RefCell<Option<String>>,
+    // renders the mip levels of the textures made on this device
+    pub mipmaps: MipmapGenerator,
+}
+
+// the texture a frame is rendered into: either a swap chain image, the offscreen target, or a
+// texture of its own for a frame being captured from a window
+pub enum Frame<'a> {
+    Surface(wgpu::SurfaceTexture),
+    Offscreen(&'a wgpu::Texture),
+    Capture(wgpu::Texture),
+}
+
+impl Frame<'_> {
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            Frame::Surface(output) => &output.texture,
+            Frame::Offscreen(texture) => texture,
+            Frame::Capture(texture) => texture,
+        }
+    }
+
+    pub fn present(self) {
+        if let Frame::Surface(output) = self {
+            output.present();
+        }
+    }
+}
+
+impl InitWgpu {
+    pub async fn init_wgpu(window: &Window) -> Self {
+        let size = window.inner_size();
+        let instance = wgpu::Instance::new(wgpu::Backends::VULKAN);
+        // let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let surface = unsafe { instance.create_surface(window) };
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .unwrap();
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features: wgpu::Features::empty(),
+                    limits: wgpu::Limits::default(),
+                },
+                None, // Trace path
+            )
+            .await
+            .unwrap();
+
+        // surfaces need not support COPY_SRC, so captured frames are rendered into a texture of
+        // their own instead; see get_current_frame
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: surface.get_supported_formats(&adapter)[0],
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        };
+        surface.configure(&device, &config);
+        let mipmaps = MipmapGenerator::new(&device);
+
+        Self {
+            surface: Some(surface),
+            device,
+            queue,
+            config,
+            size,
+            offscreen: None,
+            capture_file: RefCell::new(None),
+            mipmaps,
+        }
+    }
+
+    // create a device without a window and render into an offscreen texture of the given size
+    // and format. the backend can be chosen with WGPU_BACKEND; when no hardware adapter is
+    // available, or WGPU_FORCE_FALLBACK_ADAPTER=1 is set, the software fallback adapter is used.
+    pub async fn init_headless(width: u32, height: u32, format: wgpu::TextureFormat) -> Self {
+        let size = winit::dpi::PhysicalSize::new(width, height);
+        let backends = wgpu::util::backend_bits_from_env().unwrap_or_else(wgpu::Backends::all);
+        let force_fallback = std::env::var("WGPU_FORCE_FALLBACK_ADAPTER").as_deref() == Ok("1");
+        let instance = wgpu::Instance::new(backends);
+        let mut adapter = None;
+        if !force_fallback {
+            adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::default(),
+                    compatible_surface: None,
+                    force_fallback_adapter: false,
+                })
+                .await;
+        }
+        if adapter.is_none() {
+            adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::default(),
+                    compatible_surface: None,
+                    force_fallback_adapter: true,
+                })
+                .await;
+        }
+        let adapter = adapter.expect("Failed to find a hardware or fallback adapter");
+
+        // software rasterizers often fall short of the default limits, so ask for what the
+        // adapter actually offers
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features: wgpu::Features::empty(),
+                    limits: adapter.limits(),
+                },
+                None, // Trace path
+            )
+            .await
+            .unwrap();
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+        };
+        let offscreen = create_offscreen_texture(&device, &config);
+        let mipmaps = MipmapGenerator::new(&device);
+
+        Self {
+            surface: None,
+            device,
+            queue,
+            config,
+            size,
+            offscreen: Some(offscreen),
+            capture_file: RefCell::new(None),
+            mipmaps,
+        }
+    }
+
+    // save the next frame to a png file once it has been rendered; see finish_frame
+    pub fn request_capture(&self, file_name: &str) {
+        *self.capture_file.borrow_mut() = Some(file_name.to_string());
+    }
+
+    // acquire the texture for the next frame from the surface, or hand out the offscreen target.
+    // a frame to be captured from a window is rendered into a texture that can be read back,
+    // and that frame is not shown
+    pub fn get_current_frame(&self) -> Result<Frame<'_>, wgpu::SurfaceError> {
+        if self.surface.is_some() && self.capture_file.borrow().is_some() {
+            let config = wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                ..self.config.clone()
+            };
+            return Ok(Frame::Capture(create_offscreen_texture(&self.device, &config)));
+        }
+        match (&self.surface, &self.offscreen) {
+            (Some(surface), _) => Ok(Frame::Surface(surface.get_current_texture()?)),
+            (None, Some(texture)) => Ok(Frame::Offscreen(texture)),
+            (None, None) => Err(wgpu::SurfaceError::Lost),
+        }
+    }
+
+    // after the frame's commands are submitted: save it if a capture was requested, and show it.
+    // the frame is shown even when saving it fails
+    pub fn finish_frame(&self, frame: Frame) -> anyhow::Result<()> {
+        let saved = match self.capture_file.take() {
+            Some(file_name) => {
+                let texture = frame.texture();
+                capture::save_frame(&self.device, &self.queue, texture, &self.config, &file_name)
+            }
+            None => Ok(()),
+        };
+        frame.present();
+        saved
+    }
+
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        self.size = new_size;
+        self.config.width = new_size.width;
+        self.config.height = new_size.height;
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+        if self.offscreen.is_some() {
+            self.offscreen = Some(create_offscreen_texture(&self.device, &self.config));
+        }
+    }
+}
+
+fn create_offscreen_texture(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Texture"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: config.usage,
+    })
+}
+
+pub fn create_transforms(
+    translation: [f32; 3],
+    rotation: [f32; 3],
+    scaling: [f32; 3],
+) -> Matrix4<f32> {
+    // create individual transformation matrices
+    let trans_mat =
+        Matrix4::from_translation(Vector3::new(translation[0], translation[1], translation[2]));
+    let rotate_mat_x = Matrix4::from_angle_x(Rad(rotation[0]));
+    let rotate_mat_y = Matrix4::from_angle_y(Rad(rotation[1]));
+    let rotate_mat_z = Matrix4::from_angle_z(Rad(rotation[2]));
+    let scale_mat = Matrix4::from_nonuniform_scale(scaling[0], scaling[1], scaling[2]);
+
+    // combine all transformation matrices together to form a final transform matrix: model matrix
+    let model_mat = trans_mat * rotate_mat_z * rotate_mat_y * rotate_mat_x * scale_mat;
+
+    // return final model matrix
+    model_mat
+}
+
+pub const OPENGL_TO_WGPU_MATRIX: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.5, 1.0,
+);
+
+// maps depth d to 1 - d, so that the near plane lands at 1 and the far plane at 0
+const REVERSE_Z_MATRIX: Matrix4<f32> = Matrix4::new(
+    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0, 1.0,
+);
+
+// projection parameters that can be kept around and updated when the window changes shape.
+// the orthographic view box is ortho_height tall and keeps the aspect ratio of the window.
+// with reverse_z the depth runs from 1 at the near plane to 0 at the far plane. stored in a float
+// depth buffer, whose precision is finest near 0, this spreads the precision much more evenly
+// over distance; the depth format, test and clear value have to follow, see depth_format,
+// depth_compare and depth_clear.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Projection {
+    pub is_perspective: bool,
+    // vertical field of view of the perspective projection
+    pub fovy: Rad<f32>,
+    pub ortho_height: f32,
+    pub near: f32,
+    pub far: f32,
+    pub aspect: f32,
+    pub reverse_z: bool,
+}
+
+impl Projection {
+    // 72 degree field of view, near 0.1, far 100
+    pub fn perspective(aspect: f32) -> Self {
+        Self {
+            is_perspective: true,
+            fovy: Rad(2.0 * PI / 5.0),
+            ortho_height: 6.0,
+            near: 0.1,
+            far: 100.0,
+            aspect,
+            reverse_z: false,
+        }
+    }
+
+    // a box 6 units tall, 8 wide at 4:3, from 1 unit behind the camera to 6 in front of it
+    pub fn orthographic(aspect: f32) -> Self {
+        Self {
+            is_perspective: false,
+            near: -1.0,
+            far: 6.0,
+            ..Self::perspective(aspect)
+        }
+    }
+
+    pub fn new(aspect: f32, is_perspective: bool) -> Self {
+        if is_perspective {
+            Self::perspective(aspect)
+        } else {
+            Self::orthographic(aspect)
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f32 / height as f32;
+        }
+    }
+
+    pub fn matrix(&self) -> Matrix4<f32> {
+        let project_mat = if self.is_perspective {
+            OPENGL_TO_WGPU_MATRIX * perspective(self.fovy, self.aspect, self.near, self.far)
+        } else {
+            let top = 0.5 * self.ortho_height;
+            let right = top * self.aspect;
+            OPENGL_TO_WGPU_MATRIX * ortho(-right, right, -top, top, self.near, self.far)
+        };
+        if self.reverse_z {
+            REVERSE_Z_MATRIX * project_mat
+        } else {
+            project_mat
+        }
+    }
+
+    // the depth buffer format for pipelines drawn with this projection. reverse z gains nothing
+    // with a fixed point format such as Depth24Plus
+    pub fn depth_format(&self) -> wgpu::TextureFormat {
+        if self.reverse_z {
+            wgpu::TextureFormat::Depth32Float
+        } else {
+            wgpu::TextureFormat::Depth24Plus
+        }
+    }
+
+    // the depth comparison for pipelines drawn with this projection
+    pub fn depth_compare(&self) -> wgpu::CompareFunction {
+        if self.reverse_z {
+            wgpu::CompareFunction::GreaterEqual
+        } else {
+            wgpu::CompareFunction::LessEqual
+        }
+    }
+
+    // the value the depth buffer is cleared to: the far plane
+    pub fn depth_clear(&self) -> f32 {
+        if self.reverse_z {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+pub fn create_view_projection(
+    camera_position: Point3<f32>,
+    look_direction: Point3<f32>,
+    up_direction: Vector3<f32>,
+    aspect: f32,
+    is_perspective: bool,
+) -> (Matrix4<f32>, Matrix4<f32>, Matrix4<f32>) {
+    // construct view matrix
+    let view_mat = Matrix4::look_at_rh(camera_position, look_direction, up_direction);
+    // construct projection matrix
+    let project_mat = Projection::new(aspect, is_perspective).matrix();
+
+    // construct view-projection matrix
+    let view_project_mat = project_mat * view_mat;
+    // return various matrices
+    (view_mat, project_mat, view_project_mat)
+}
+
+pub fn create_view_projection_ortho(
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+    near: f32,
+    far: f32,
+    camera_position: Point3<f32>,
+    look_direction: Point3<f32>,
+    up_direction: Vector3<f32>,
+) -> (Matrix4<f32>, Matrix4<f32>, Matrix4<f32>) {
+    // construct view matrix
+    let view_mat = Matrix4::look_at_rh(camera_position, look_direction, up_direction);
+    // construct projection matrix
+    let project_mat = OPENGL_TO_WGPU_MATRIX * ortho(left, right, bottom, top, near, far);
+
+    // construct view-projection matrix
+    let view_project_mat = project_mat * view_mat;
+    // return various matrices
+    (view_mat, project_mat, view_project_mat)
+}
+
+pub fn create_view(
+    camera_position: Point3<f32>,
+    look_direction: Point3<f32>,
+    up_direction: Vector3<f32>,
+) -> Matrix4<f32> {
+    Matrix4::look_at_rh(camera_position, look_direction, up_direction)
+}
+
+pub fn create_projection(aspect: f32, is_perspective: bool) -> Matrix4<f32> {
+    Projection::new(aspect, is_perspective).matrix()
+}
@@ -0,0 +1,463 @@
+use cgmath::*; 
+use crate::math_func; 
+use crate::mesh::{grid_indices, smooth_normals, Mesh, NormalMode}; 
+
+pub fn cube_data() -> (Vec<[i8; 3]>, Vec<[i8; 3]>, Vec<[i8; 2]>, Vec<[i8; 3]>) { 
+    let positions = [ 
+        // front (0, 0, 1)
+        [-1, -1, 1], [1, -1, 1], [-1, 1, 1], [-1, 1, 1], [ 1, -1, 1], [ 1, 1, 1], 
+        // right (1, 0, 0)
+        [ 1, -1, 1], [1, -1, -1], [ 1, 1, 1], [ 1, 1, 1], [ 1, -1, -1], [ 1, 1, -1], 
+        // back (0, 0, -1)
+        [ 1, -1, -1], [-1, -1, -1], [1, 1, -1], [ 1, 1, -1], [-1, -1, -1], [-1, 1, -1], 
+        // left (-1, 0, 0)
+        [-1, -1, -1], [-1, -1, 1], [-1, 1, -1], [-1, 1, -1], [-1, -1, 1], [-1, 1, 1], 
+        // top (0, 1, 0)
+        [-1, 1, 1], [ 1, 1, 1], [-1, 1, -1], [-1, 1, -1], [ 1, 1, 1], [ 1, 1, -1],
+        // bottom (0, -1, 0)
+        [-1, -1, -1], [ 1, -1, -1], [-1, -1, 1], [-1, -1, 1], [ 1, -1, -1], [ 1, -1, 1], 
+    ]; 
+
+    let colors = [ 
+        // front - blue
+        [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], 
+        // right - red
+        [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], 
+        // back - yellow 
+        [1, 1, 0], [1, 1, 0], [1, 1, 0], [1, 1, 0], [1, 1, 0], [1, 1, 0], 
+        // left - aqua
+        [0, 1, 1], [0, 1, 1], [0, 1, 1], [0, 1, 1], [0, 1, 1], [0, 1, 1], 
+        // top - green
+        [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], 
+        // bottom - fuchsia
+        [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1], [1, 0, 1], 
+    ]; 
+
+    let uvs= [ 
+        // front
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+        // right
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+        // back
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+        // left
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+        // top
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+        // bottom
+        [0, 0], [1, 0], [0, 1], [0, 1], [1, 0], [1, 1], 
+    ]; 
+
+    let normals = [ 
+        // front 
+        [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], [0, 0, 1], 
+        // right 
+        [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], [1, 0, 0], 
+        // back 
+        [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1], [0, 0, -1], 
+        // left 
+        [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], [-1, 0, 0], 
+        // top 
+        [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], [0, 1, 0], 
+        // bottom
+        [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0], [0, -1, 0], 
+    ]; 
+
+    // return data
+    (positions.to_vec(), colors.to_vec(), uvs.to_vec(), normals.to_vec()) 
+} 
+
+pub fn cube_mesh() -> Mesh { 
+    let (positions, colors, uvs, normals) = cube_data(); 
+    let to_f32 = |a: &[i8; 3]| [a[0] as f32, a[1] as f32, a[2] as f32]; 
+    Mesh { 
+        positions: positions.iter().map(to_f32).collect(), 
+        normals: normals.iter().map(to_f32).collect(), 
+        colors: colors.iter().map(to_f32).collect(), 
+        uvs: uvs.iter().map(|t| [t[0] as f32, t[1] as f32]).collect(), 
+        indices: None, 
+    } 
+} 
+
+pub fn cube_data_index() -> (Vec<[i8; 3]>, Vec<[i8; 3]>, Vec<u16>) { 
+    let positions = [ 
+        [-1, -1,  1], // vertex a
+        [ 1, -1,  1], // vertex b
+        [ 1,  1,  1], // vertex c
+        [-1,  1,  1], // vertex d
+        [-1, -1, -1], // vertex e
+        [ 1, -1, -1], // vertex f
+        [ 1,  1, -1], // vertex g
+        [-1,  1, -1], // vertex h
+    ]; 
+
+    let colors = [ 
+        [0, 0, 1], // vertex a
+        [1, 0, 1], // vertex b
+        [1, 1, 1], // vertex c
+        [0, 1, 1], // vertex d
+        [0, 0, 0], // vertex e
+        [1, 0, 0], // vertex f
+        [1, 1, 0], // vertex g
+        [0, 1, 0], // vertex h
+    ]; 
+
+    let indices = [ 
+        0, 1, 2, 2, 3, 0, // front 
+        1, 5, 6, 6, 2, 1, // right 
+        4, 7, 6, 6, 5, 4, // back 
+        0, 3, 7, 7, 4, 0, // left 
+        3, 2, 6, 6, 7, 3, // top 
+        0, 4, 5, 5, 1, 0, // bottom
+    ]; 
+
+    (positions.to_vec(), colors.to_vec(), indices.to_vec()) 
+} 
+
+pub fn sphere_data(r: f32, u:usize, v:usize) -> Mesh { 
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity((4* (u - 1)*(v -1)) as usize); 
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity((4* (u - 1)*(v -1)) as usize); 
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity((4* (u - 1)*(v -1)) as usize); 
+    for i in 0..u - 1 { 
+        for j in 0..v - 1 { 
+            let theta = i as f32 *180.0/(u as f32 - 1.0); 
+            let phi = j as f32 * 360.0/(v as f32 - 1.0); 
+            let theta1 = (i as f32 + 1.0) *180.0/(u as f32 - 1.0); 
+            let phi1 = (j as f32 + 1.0) * 360.0/(v as f32 - 1.0); 
+            let p0 = math_func::sphere_position(r, Deg(theta) , Deg(phi)); 
+            let p1 = math_func::sphere_position(r, Deg(theta1) , Deg(phi)); 
+            let p2 = math_func::sphere_position(r, Deg(theta1) , Deg(phi1)); 
+            let p3 = math_func::sphere_position(r, Deg(theta) , Deg(phi1)); 
+
+            // positions
+            positions.push(p0); 
+            positions.push(p1); 
+            positions.push(p3); 
+            positions.push(p1); 
+            positions.push(p2); 
+            positions.push(p3); 
+
+            // normals
+            normals.push([p0[0]/r, p0[1]/r, p0[2]/r]); 
+            normals.push([p1[0]/r, p1[1]/r, p1[2]/r]); 
+            normals.push([p3[0]/r, p3[1]/r, p3[2]/r]); 
+            normals.push([p1[0]/r, p1[1]/r, p1[2]/r]); 
+            normals.push([p2[0]/r, p2[1]/r, p2[2]/r]); 
+            normals.push([p3[0]/r, p3[1]/r, p3[2]/r]); 
+
+            // uvs: spherical mapping, u around the axis and v from the north pole down
+            let uv0 = [phi/360.0, theta/180.0]; 
+            let uv1 = [phi/360.0, theta1/180.0]; 
+            let uv2 = [phi1/360.0, theta1/180.0]; 
+            let uv3 = [phi1/360.0, theta/180.0]; 
+            uvs.push(uv0); 
+            uvs.push(uv1); 
+            uvs.push(uv3); 
+            uvs.push(uv1); 
+            uvs.push(uv2); 
+            uvs.push(uv3); 
+        } 
+    } 
+    Mesh { 
+        positions, 
+        normals, 
+        uvs, 
+        ..Default::default() 
+    } 
+} 
+
+pub fn cone_data(rtop: f32, rbottom: f32, height: f32, n:usize) -> Mesh { 
+    let h = height / 2.0; 
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(12 * (n-1)); 
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(12 * (n -1)); 
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(12 * (n -1)); 
+    let rmax = rtop.max(rbottom); 
+    for i in 0..n - 1 { 
+        let theta = i as f32 *360.0/(n as f32 - 1.0); 
+        let theta1 = (i as f32 + 1.0) *360.0/(n as f32 - 1.0); 
+        let p0 = math_func::cylinder_position(rtop, h, Deg(theta)); 
+        let p1 = math_func::cylinder_position(rbottom, -h, Deg(theta)); 
+        let p2 = math_func::cylinder_position(0.0, -h, Deg(theta)); 
+        let p3 = math_func::cylinder_position(0.0, h, Deg(theta)); 
+        let p4 = math_func::cylinder_position(rtop, h, Deg(theta1)); 
+        let p5 = math_func::cylinder_position(rbottom, -h, Deg(theta1)); 
+
+        // positions
+        // top face
+        positions.push(p0); 
+        positions.push(p4); 
+        positions.push(p3); 
+
+        // bottom face
+        positions.push(p1); 
+        positions.push(p2); 
+        positions.push(p5); 
+
+        // outer face
+        positions.push(p0); 
+        positions.push(p1); 
+        positions.push(p5); 
+        positions.push(p5); 
+        positions.push(p4); 
+        positions.push(p0); 
+
+        // normals
+        let ca = Vector3::new(p5[0]-p0[0], p5[1]-p0[1], p5[2]-p0[2]); 
+        let db = Vector3::new(p4[0]-p1[0], p4[1]-p1[1], p4[2]-p1[2]); 
+        let cp = (ca.cross(db)).normalize(); 
+
+        // top face
+        normals.push([0.0, 1.0, 0.0]); 
+        normals.push([0.0, 1.0, 0.0]); 
+        normals.push([0.0, 1.0, 0.0]); 
+
+        // bottom face
+        normals.push([0.0, -1.0, 0.0]); 
+        normals.push([0.0, -1.0, 0.0]); 
+        normals.push([0.0, -1.0, 0.0]); 
+
+        // outer face
+        normals.push([cp[0], cp[1], cp[2]]); 
+        normals.push([cp[0], cp[1], cp[2]]); 
+        normals.push([cp[0], cp[1], cp[2]]); 
+        normals.push([cp[0], cp[1], cp[2]]); 
+        normals.push([cp[0], cp[1], cp[2]]); 
+        normals.push([cp[0], cp[1], cp[2]]); 
+
+        // uvs: planar mapping on the caps, cylindrical mapping on the side
+        let cap = |p: [f32; 3]| [0.5 + 0.5 * p[0] / rmax, 0.5 + 0.5 * p[2] / rmax]; 
+        uvs.push(cap(p0)); 
+        uvs.push(cap(p4)); 
+        uvs.push(cap(p3)); 
+        uvs.push(cap(p1)); 
+        uvs.push(cap(p2)); 
+        uvs.push(cap(p5)); 
+        let (u0, u1) = (theta/360.0, theta1/360.0); 
+        uvs.push([u0, 0.0]); 
+        uvs.push([u0, 1.0]); 
+        uvs.push([u1, 1.0]); 
+        uvs.push([u1, 1.0]); 
+        uvs.push([u1, 0.0]); 
+        uvs.push([u0, 0.0]); 
+    } 
+    Mesh { 
+        positions, 
+        normals, 
+        uvs, 
+        ..Default::default() 
+    } 
+} 
+
+pub fn torus_data(r_torus:f32, r_tube:f32, n_torus:usize, n_tube:usize, normal_mode: NormalMode) -> Mesh { 
+    let smooth = torus_normals(r_torus, r_tube, n_torus, n_tube, normal_mode); 
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity((4* (n_torus - 1)*(n_tube -1)) as usize); 
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity((4* (n_torus - 1)*(n_tube -1)) as usize); 
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity((4* (n_torus - 1)*(n_tube -1)) as usize); 
+    for i in 0..n_torus - 1 { 
+        for j in 0..n_tube - 1 { 
+            let u = i as f32 * 360.0/(n_torus as f32 - 1.0); 
+            let v = j as f32 * 360.0/(n_tube as f32 - 1.0); 
+            let u1 = (i as f32 + 1.0) * 360.0/(n_torus as f32 - 1.0); 
+            let v1 = (j as f32 + 1.0) * 360.0/(n_tube as f32 - 1.0); 
+            let p0 = math_func::torus_position(r_torus, r_tube, Deg(u), Deg(v)); 
+            let p1 = math_func::torus_position(r_torus, r_tube, Deg(u1), Deg(v)); 
+            let p2 = math_func::torus_position(r_torus, r_tube, Deg(u1), Deg(v1)); 
+            let p3 = math_func::torus_position(r_torus, r_tube, Deg(u), Deg(v1)); 
+
+            // positions
+            positions.push(p0); 
+            positions.push(p1); 
+            positions.push(p2); 
+            positions.push(p2); 
+            positions.push(p3); 
+            positions.push(p0); 
+
+            // uvs
+            let uv0 = [u/360.0, v/360.0]; 
+            let uv1 = [u1/360.0, v/360.0]; 
+            let uv2 = [u1/360.0, v1/360.0]; 
+            let uv3 = [u/360.0, v1/360.0]; 
+            uvs.extend_from_slice(&[uv0, uv1, uv2, uv2, uv3, uv0]); 
+
+            // normals
+            if let Some(smooth) = &smooth { 
+                let [n0, n1, n2, n3] = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)] 
+                    .map(|(a, b)| smooth[(a % (n_torus - 1)) * (n_tube - 1) + b % (n_tube - 1)]); 
+                normals.extend_from_slice(&[n0, n1, n2, n2, n3, n0]); 
+                continue; 
+            } 
+            let ca = Vector3::new(p2[0]-p0[0], p2[1]-p0[1], p2[2]-p0[2]); 
+            let db = Vector3::new(p3[0]-p1[0], p3[1]-p1[1], p3[2]-p1[2]); 
+            let cp = (ca.cross(db)).normalize(); 
+            normals.push([cp[0], cp[1], cp[2]]); 
+
+            normals.push([cp[0], cp[1], cp[2]]); 
+            normals.push([cp[0], cp[1], cp[2]]); 
+            normals.push([cp[0], cp[1], cp[2]]); 
+            normals.push([cp[0], cp[1], cp[2]]); 
+            normals.push([cp[0], cp[1], cp[2]]); 
+        } 
+    } 
+    Mesh { 
+        positions, 
+        normals, 
+        uvs, 
+        ..Default::default() 
+    } 
+}
+
+// vertex normals of the torus for the smooth modes, one per point of the closed
+// (n_torus - 1) x (n_tube - 1) grid so that the averaged normals also wrap around the seams
+fn torus_normals(r_torus: f32, r_tube: f32, n_torus: usize, n_tube: usize, normal_mode: NormalMode) -> Option<Vec<[f32; 3]>> {
+    let (nu, nv) = (n_torus - 1, n_tube - 1);
+    let angles = |i: usize, j: usize| {
+        (Deg(i as f32 * 360.0 / nu as f32), Deg(j as f32 * 360.0 / nv as f32))
+    };
+    match normal_mode {
+        NormalMode::Flat => None,
+        NormalMode::Analytic => Some(
+            (0..nu * nv)
+                .map(|k| {
+                    let (u, v) = angles(k / nv, k % nv);
+                    math_func::torus_position(0.0, 1.0, u, v)
+                })
+                .collect(),
+        ),
+        NormalMode::AreaWeighted | NormalMode::AngleWeighted => {
+            let positions: Vec<[f32; 3]> = (0..nu * nv)
+                .map(|k| {
+                    let (u, v) = angles(k / nv, k % nv);
+                    math_func::torus_position(r_torus, r_tube, u, v)
+                })
+                .collect();
+            let mut indices: Vec<u32> = Vec::with_capacity(6 * nu * nv);
+            for i in 0..nu {
+                for j in 0..nv {
+                    let [c0, c1, c2, c3] = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)]
+                        .map(|(a, b)| ((a % nu) * nv + b % nv) as u32);
+                    indices.extend_from_slice(&[c0, c1, c2, c2, c3, c0]);
+                }
+            }
+            Some(smooth_normals(&positions, &indices, normal_mode == NormalMode::AngleWeighted))
+        }
+    }
+}
+
+// indexed variants: each generator below emits a shared vertex grid plus triangle indices
+// (use Mesh::index_data to pick the index format). the triangles keep the winding of the
+// non-indexed versions above, and because vertices are shared their normals are per vertex.
+
+pub fn sphere_data_index(r: f32, u: usize, v: usize) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(u * v);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(u * v);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(u * v);
+    for i in 0..u {
+        for j in 0..v {
+            let theta = i as f32 * 180.0 / (u as f32 - 1.0);
+            let phi = j as f32 * 360.0 / (v as f32 - 1.0);
+            let p = math_func::sphere_position(r, Deg(theta), Deg(phi));
+            positions.push(p);
+            normals.push([p[0] / r, p[1] / r, p[2] / r]);
+            uvs.push([phi / 360.0, theta / 180.0]);
+        }
+    }
+    Mesh {
+        positions,
+        normals,
+        uvs,
+        indices: Some(grid_indices(u, v, [(0, 0), (1, 0), (1, 1), (0, 1)])),
+        ..Default::default()
+    }
+}
+
+pub fn cone_data_index(rtop: f32, rbottom: f32, height: f32, n: usize) -> Mesh {
+    let h = height / 2.0;
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(4 * n + 2);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(4 * n + 2);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(4 * n + 2);
+    let mut indices: Vec<u32> = Vec::with_capacity(12 * n.saturating_sub(1));
+
+    // cap centers, then four rings of n vertices: top cap, bottom cap, side top, side bottom
+    // the caps use a planar mapping, the side a cylindrical one
+    let rmax = rtop.max(rbottom);
+    let cap = |p: [f32; 3]| [0.5 + 0.5 * p[0] / rmax, 0.5 + 0.5 * p[2] / rmax];
+    positions.push(math_func::cylinder_position(0.0, h, Deg(0.0)));
+    normals.push([0.0, 1.0, 0.0]);
+    uvs.push([0.5, 0.5]);
+    positions.push(math_func::cylinder_position(0.0, -h, Deg(0.0)));
+    normals.push([0.0, -1.0, 0.0]);
+    uvs.push([0.5, 0.5]);
+    let top = 2;
+    let bottom = top + n;
+    let side_top = bottom + n;
+    let side_bottom = side_top + n;
+    for i in 0..n {
+        let theta = i as f32 * 360.0 / (n as f32 - 1.0);
+        let p = math_func::cylinder_position(rtop, h, Deg(theta));
+        positions.push(p);
+        normals.push([0.0, 1.0, 0.0]);
+        uvs.push(cap(p));
+    }
+    for i in 0..n {
+        let theta = i as f32 * 360.0 / (n as f32 - 1.0);
+        let p = math_func::cylinder_position(rbottom, -h, Deg(theta));
+        positions.push(p);
+        normals.push([0.0, -1.0, 0.0]);
+        uvs.push(cap(p));
+    }
+    for (r, y, uv_v) in [(rtop, h, 0.0), (rbottom, -h, 1.0)] {
+        for i in 0..n {
+            let theta = i as f32 * 360.0 / (n as f32 - 1.0);
+            positions.push(math_func::cylinder_position(r, y, Deg(theta)));
+            // outward normal of the slanted side at this angle
+            let radial = math_func::cylinder_position(1.0, 0.0, Deg(theta));
+            let normal = Vector3::new(height * radial[0], rbottom - rtop, height * radial[2]).normalize();
+            normals.push([normal[0], normal[1], normal[2]]);
+            uvs.push([theta / 360.0, uv_v]);
+        }
+    }
+
+    for i in 0..n.saturating_sub(1) {
+        let (i0, i1) = (i as u32, i as u32 + 1);
+        let (top, bottom) = (top as u32, bottom as u32);
+        let (side_top, side_bottom) = (side_top as u32, side_bottom as u32);
+        // top face
+        indices.extend_from_slice(&[top + i0, top + i1, 0]);
+        // bottom face
+        indices.extend_from_slice(&[bottom + i0, 1, bottom + i1]);
+        // outer face
+        indices.extend_from_slice(&[side_top + i0, side_bottom + i0, side_bottom + i1]);
+        indices.extend_from_slice(&[side_bottom + i1, side_top + i1, side_top + i0]);
+    }
+
+    Mesh {
+        positions,
+        normals,
+        uvs,
+        indices: Some(indices),
+        ..Default::default()
+    }
+}
+
+pub fn torus_data_index(r_torus: f32, r_tube: f32, n_torus: usize, n_tube: usize) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(n_torus * n_tube);
+    let mut normals: Vec<[f32; 3]> = Vec::with_capacity(n_torus * n_tube);
+    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(n_torus * n_tube);
+    for i in 0..n_torus {
+        for j in 0..n_tube {
+            let u = Deg(i as f32 * 360.0 / (n_torus as f32 - 1.0));
+            let v = Deg(j as f32 * 360.0 / (n_tube as f32 - 1.0));
+            positions.push(math_func::torus_position(r_torus, r_tube, u, v));
+            // the normal points from the tube center to the surface
+            normals.push(math_func::torus_position(0.0, 1.0, u, v));
+            uvs.push([u.0 / 360.0, v.0 / 360.0]);
+        }
+    }
+    Mesh {
+        positions,
+        normals,
+        uvs,
+        indices: Some(grid_indices(n_torus, n_tube, [(0, 0), (1, 0), (1, 1), (0, 1)])),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,190 @@
+// winit 0.27 still requires the deprecated modifiers fields when building input events
+#![allow(deprecated)]
+use cgmath::{InnerSpace, Point3, Transform};
+use hello_world::camera::{Camera, CameraController, CameraMode};
+use winit::dpi::PhysicalPosition;
+use winit::event::*;
+
+fn close(a: Point3<f32>, b: Point3<f32>) -> bool {
+    (a - b).magnitude() < 1e-4
+}
+
+// what every synthesized event carries
+fn events() -> (DeviceId, ModifiersState) {
+    (unsafe { DeviceId::dummy() }, ModifiersState::empty())
+}
+
+fn drag(camera: &mut CameraController, button: MouseButton, from: (f64, f64), to: (f64, f64)) {
+    let (device_id, modifiers) = events();
+    let moved = |(x, y)| WindowEvent::CursorMoved {
+        device_id,
+        position: PhysicalPosition::new(x, y),
+        modifiers,
+    };
+    let button_event = |state| WindowEvent::MouseInput {
+        device_id,
+        state,
+        button,
+        modifiers,
+    };
+    camera.process_event(&moved(from));
+    assert!(camera.process_event(&button_event(ElementState::Pressed)));
+    assert!(camera.process_event(&moved(to)));
+    camera.process_event(&button_event(ElementState::Released));
+}
+
+#[test]
+fn starts_at_the_given_eye() {
+    let eye = Point3::new(2.5, 1.25, 2.5);
+    let camera = CameraController::new(eye, Point3::new(0.0, 0.0, 0.0));
+    assert!(close(camera.eye(), eye));
+
+    // the view matrix puts the target straight ahead of the eye
+    let target = camera
+        .view_matrix()
+        .transform_point(Point3::new(0.0, 0.0, 0.0));
+    assert!(close(target, Point3::new(0.0, 0.0, -camera.distance)));
+}
+
+#[test]
+fn mouse_orbits_pans_and_zooms() {
+    let target = Point3::new(1.0, 0.0, 0.0);
+    let mut camera = CameraController::new(Point3::new(1.0, 0.0, 4.0), target);
+
+    // orbiting keeps the distance to the target
+    drag(
+        &mut camera,
+        MouseButton::Left,
+        (100.0, 100.0),
+        (160.0, 130.0),
+    );
+    assert!((camera.yaw + 0.6).abs() < 1e-5 && (camera.pitch - 0.3).abs() < 1e-5);
+    assert!(((camera.eye() - target).magnitude() - 4.0).abs() < 1e-4);
+
+    // panning moves the eye and the target together
+    let offset = camera.eye() - camera.target;
+    drag(
+        &mut camera,
+        MouseButton::Right,
+        (160.0, 130.0),
+        (100.0, 130.0),
+    );
+    assert!(!close(camera.target, target));
+    assert!((camera.eye() - camera.target - offset).magnitude() < 1e-4);
+
+    let (device_id, modifiers) = events();
+    let wheel = WindowEvent::MouseWheel {
+        device_id,
+        delta: MouseScrollDelta::LineDelta(0.0, 2.0),
+        phase: TouchPhase::Moved,
+        modifiers,
+    };
+    camera.process_event(&wheel);
+    assert!((camera.distance - 4.0 * 0.81).abs() < 1e-4);
+    assert!((camera.zoom_factor() - 0.81).abs() < 1e-4);
+
+    // r brings back the initial view
+    let key = WindowEvent::KeyboardInput {
+        device_id,
+        input: KeyboardInput {
+            scancode: 0,
+            state: ElementState::Pressed,
+            virtual_keycode: Some(VirtualKeyCode::R),
+            modifiers,
+        },
+        is_synthetic: false,
+    };
+    assert!(camera.process_event(&key));
+    assert!(close(camera.eye(), Point3::new(1.0, 0.0, 4.0)) && close(camera.target, target));
+}
+
+#[test]
+fn pitch_and_distance_are_limited() {
+    let mut camera = CameraController::new(Point3::new(0.0, 0.0, 2.0), Point3::new(0.0, 0.0, 0.0));
+    camera.rotate(0.0, 10.0);
+    assert!(camera.pitch < std::f32::consts::FRAC_PI_2);
+    assert!(camera.view_matrix().x.x.is_finite());
+    camera.zoom(1000.0);
+    assert_eq!(camera.distance, camera.min_distance);
+    camera.zoom(-1000.0);
+    assert_eq!(camera.distance, camera.max_distance);
+}
+
+fn same(a: cgmath::Matrix4<f32>, b: cgmath::Matrix4<f32>) -> bool {
+    let d = a - b;
+    [d.x, d.y, d.z, d.w].iter().all(|c| c.magnitude() < 1e-4)
+}
+
+fn key(camera: &mut Camera, key: VirtualKeyCode, state: ElementState) -> bool {
+    let (device_id, modifiers) = events();
+    let input = KeyboardInput {
+        scancode: 0,
+        state,
+        virtual_keycode: Some(key),
+        modifiers,
+    };
+    camera.process_event(&WindowEvent::KeyboardInput {
+        device_id,
+        input,
+        is_synthetic: false,
+    })
+}
+
+#[test]
+fn fly_movement_scales_with_frame_time() {
+    let mut camera = Camera::new(Point3::new(0.0, 0.0, 5.0), Point3::new(0.0, 0.0, 0.0));
+    // wasd only drives the fly camera
+    assert!(!key(&mut camera, VirtualKeyCode::W, ElementState::Pressed));
+    assert!(key(&mut camera, VirtualKeyCode::Tab, ElementState::Pressed));
+    assert_eq!(camera.mode, CameraMode::Fly);
+    assert!(close(camera.eye(), Point3::new(0.0, 0.0, 5.0)));
+
+    camera.fly.speed = 2.0;
+    assert!(key(&mut camera, VirtualKeyCode::W, ElementState::Pressed));
+    camera.update(0.25);
+    camera.update(0.25);
+    assert!(close(camera.eye(), Point3::new(0.0, 0.0, 4.0)));
+    camera.update(1.0);
+    assert!(close(camera.eye(), Point3::new(0.0, 0.0, 2.0)));
+
+    key(&mut camera, VirtualKeyCode::W, ElementState::Released);
+    camera.update(1.0);
+    assert!(close(camera.eye(), Point3::new(0.0, 0.0, 2.0)));
+}
+
+#[test]
+fn switching_modes_keeps_the_view() {
+    let mut camera = Camera::new(Point3::new(2.5, 1.25, 2.5), Point3::new(0.0, 0.0, 0.0));
+    let view = camera.view_matrix();
+    camera.set_mode(CameraMode::Fly);
+    assert!(same(camera.view_matrix(), view));
+
+    // fly sideways, then orbit around the point now straight ahead
+    camera.fly.position += cgmath::Vector3::new(1.0, 0.0, -1.0);
+    let view = camera.view_matrix();
+    camera.set_mode(CameraMode::Orbit);
+    assert!(same(camera.view_matrix(), view));
+
+    // a button released in fly mode does not leave the orbit dragging
+    let (device_id, modifiers) = events();
+    let button = |state| WindowEvent::MouseInput {
+        device_id,
+        state,
+        button: MouseButton::Left,
+        modifiers,
+    };
+    let moved = |x, y| WindowEvent::CursorMoved {
+        device_id,
+        position: PhysicalPosition::new(x, y),
+        modifiers,
+    };
+    camera.process_event(&moved(100.0, 100.0));
+    camera.process_event(&button(ElementState::Pressed));
+    key(&mut camera, VirtualKeyCode::Tab, ElementState::Pressed);
+    camera.process_event(&button(ElementState::Released));
+    key(&mut camera, VirtualKeyCode::Tab, ElementState::Pressed);
+    assert_eq!(camera.mode, CameraMode::Orbit);
+    let view = camera.view_matrix();
+    camera.process_event(&moved(300.0, 200.0));
+    assert!(same(camera.view_matrix(), view));
+}
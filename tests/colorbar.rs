@@ -0,0 +1,68 @@
+use hello_world::colorbar::{glyph, label, Colorbar, ColorbarPosition};
+
+#[test]
+fn labels_tell_ticks_apart() {
+    let colorbar = Colorbar::new(ColorbarPosition::Right);
+    let values = colorbar.tick_values([-1.0, 1.0]);
+    assert_eq!(values, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+    let labels: Vec<String> = values.iter().map(|&v| label(v, 0.5)).collect();
+    assert_eq!(labels, ["-1.00", "-0.50", "0.00", "0.50", "1.00"]);
+
+    assert_eq!(label(20.0, 10.0), "20");
+    assert_eq!(label(0.25, 0.25), "0.25");
+    // a rounding error around zero is not printed as -0
+    assert_eq!(label(-1e-7, 0.5), "0.00");
+    assert_eq!(label(1.5e6, 5e5), "1.5e6");
+    // every character a label can use has a glyph
+    for c in "-0123456789.e+".chars() {
+        assert!(glyph(c).is_some(), "{}", c);
+    }
+}
+
+#[test]
+fn colorbars_stay_in_the_window() {
+    for position in [
+        ColorbarPosition::Left,
+        ColorbarPosition::Right,
+        ColorbarPosition::Top,
+        ColorbarPosition::Bottom,
+    ] {
+        let vertices = Colorbar::new(position).vertices(320, 240, [-123.0, 4567.0]);
+        assert_eq!(vertices.len() % 6, 0);
+        for v in &vertices {
+            assert!(
+                v.position.iter().all(|c| (-1.0..=1.0).contains(c)),
+                "{:?}",
+                position
+            );
+        }
+        // the strip runs over the whole colormap
+        let strip: Vec<f32> = vertices
+            .iter()
+            .filter(|v| v.solid == 0.0)
+            .map(|v| v.t)
+            .collect();
+        assert_eq!(strip.len(), 6);
+        assert!(strip.contains(&0.0) && strip.contains(&1.0));
+    }
+
+    // a window smaller than the labels still gets a colorbar, if a clipped one
+    for (position, width, height) in [
+        (ColorbarPosition::Right, 400, 10),
+        (ColorbarPosition::Left, 1, 1),
+        (ColorbarPosition::Bottom, 20, 400),
+        (ColorbarPosition::Top, 1, 1),
+    ] {
+        let vertices = Colorbar::new(position).vertices(width, height, [0.0, 1.0]);
+        assert_eq!(vertices.len() % 6, 0, "{:?}", position);
+    }
+
+    // the minimum is at the bottom of a vertical bar
+    let vertices = Colorbar::new(ColorbarPosition::Right).vertices(320, 240, [0.0, 1.0]);
+    let bottom = vertices
+        .iter()
+        .filter(|v| v.solid == 0.0)
+        .find(|v| v.t == 0.0)
+        .unwrap();
+    assert!(bottom.position[1] < 0.0 && bottom.position[0] > 0.0);
+}
@@ -0,0 +1,125 @@
+use hello_world::colormap::{color_lerp, colormap_data, Colormap, Interpolation};
+
+fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+    (0..3).all(|k| (a[k] - b[k]).abs() < 1e-3)
+}
+
+#[test]
+fn named_maps_blend_their_stops_in_srgb() {
+    let jet = colormap_data("jet");
+    assert!(close(color_lerp("jet", 0.0, 10.0, 0.0), jet[0]));
+    assert!(close(color_lerp("jet", 0.0, 10.0, 10.0), jet[10]));
+    assert!(close(color_lerp("jet", 0.0, 10.0, 20.0), jet[10]));
+    let mid = color_lerp("jet", 0.0, 10.0, 3.5);
+    assert!(close(mid, [0, 1, 2].map(|k| (jet[3][k] + jet[4][k]) / 2.0)));
+    // a flat range gives the first color instead of nan
+    assert!(close(
+        color_lerp("hot", 1.0, 1.0, 1.0),
+        colormap_data("hot")[0]
+    ));
+}
+
+#[test]
+fn any_number_of_stops_reversed_and_banded() {
+    let map = Colormap::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 1.0]]);
+    assert!(close(map.color(0.25), [0.5, 0.0, 0.0]));
+    assert!(close(map.color(0.75), [1.0, 0.5, 0.5]));
+
+    let reversed = map.clone().reversed();
+    assert!(close(reversed.color(0.0), [1.0, 1.0, 1.0]));
+    assert!(close(reversed.color(0.25), [1.0, 0.5, 0.5]));
+
+    // three bands take the three stops
+    let banded = map.clone().discrete(3);
+    assert!(close(banded.color(0.1), [0.0, 0.0, 0.0]));
+    assert!(close(banded.color(0.4), [1.0, 0.0, 0.0]));
+    assert!(close(banded.color(0.6), [1.0, 0.0, 0.0]));
+    assert!(close(banded.color(1.0), [1.0, 1.0, 1.0]));
+
+    // the same from a name
+    let hot = colormap_data("hot");
+    let named = Colormap::from_name("hot_r:oklab:2").unwrap();
+    assert_eq!(named.interpolation, Interpolation::Oklab);
+    assert!(named.reversed && named.bands == Some(2));
+    assert!(close(named.color(0.2), hot[10]));
+    assert!(close(named.color(0.8), hot[0]));
+}
+
+#[test]
+fn interpolation_spaces() {
+    let (black, white) = ([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+    let gray = |interpolation| {
+        Colormap::new(vec![black, white])
+            .with_interpolation(interpolation)
+            .color(0.5)[0]
+    };
+    assert!((gray(Interpolation::Srgb) - 0.5).abs() < 1e-4);
+    // half the light is brighter than the srgb midpoint
+    assert!((gray(Interpolation::LinearRgb) - 0.7354).abs() < 1e-3);
+    // oklab lightness 0.5 is an eighth of the light
+    assert!((gray(Interpolation::Oklab) - 0.3885).abs() < 1e-3);
+
+    // hsv takes the short way around the hue circle, from red to blue through magenta
+    let red_blue = Colormap::new(vec![[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+    let hsv = red_blue.clone().with_interpolation(Interpolation::Hsv);
+    assert!(close(hsv.color(0.5), [1.0, 0.0, 1.0]));
+    assert!(close(red_blue.color(0.5), [0.5, 0.0, 0.5]));
+    // the ends are the stops in every space
+    for interpolation in [
+        Interpolation::LinearRgb,
+        Interpolation::Oklab,
+        Interpolation::Hsv,
+    ] {
+        let map = red_blue.clone().with_interpolation(interpolation);
+        assert!(close(map.color(0.0), [1.0, 0.0, 0.0]));
+        assert!(close(map.color(1.0), [0.0, 0.0, 1.0]));
+    }
+}
+
+#[test]
+fn lookup_reports_unknown_names() {
+    for name in [
+        "viridis", "magma", "inferno", "plasma", "cividis", "turbo", "coolwarm", "RdBu",
+    ] {
+        assert!(hello_world::colormap::names().contains(&name));
+        let colormap = Colormap::from_name(name).unwrap();
+        assert!(colormap.stops.len() >= 10);
+        // reversed by suffix, and names are not case sensitive
+        let reversed = Colormap::from_name(&format!("{}_r", name.to_lowercase())).unwrap();
+        assert!(close(reversed.color(0.0), colormap.color(1.0)));
+    }
+    // viridis runs from dark purple to yellow through teal
+    let viridis = Colormap::from_name("viridis").unwrap();
+    assert!(close(viridis.color(0.0), [0.267, 0.004, 0.329]));
+    assert!(close(viridis.color(4.0 / 9.0), [0.149, 0.51, 0.557]));
+    assert!(close(viridis.color(1.0), [0.992, 0.906, 0.145]));
+
+    let error = Colormap::from_name("virdis").unwrap_err().to_string();
+    assert!(error.contains("unknown colormap 'virdis'"), "{}", error);
+    assert!(error.contains("jet") && error.contains("viridis") && error.contains("RdBu"));
+    assert!(Colormap::from_name("jet:bands").is_err());
+    assert!(Colormap::from_name("jet:0").is_err());
+    // the string based api keeps its jet fallback
+    assert!(close(
+        color_lerp("virdis", 0.0, 1.0, 0.0),
+        colormap_data("jet")[0]
+    ));
+    assert_eq!(
+        Colormap::from_name_or_jet("virdis"),
+        Colormap::from_name("jet").unwrap()
+    );
+    assert_eq!(
+        Colormap::from_name_or_jet("hot_r"),
+        Colormap::from_name("hot_r").unwrap()
+    );
+}
+
+#[test]
+fn colormap_images_sample_the_ends() {
+    let viridis = Colormap::from_name("viridis").unwrap();
+    let img = hello_world::texture_data::colormap_image(&viridis, 256);
+    assert_eq!(img.dimensions(), (256, 1));
+    let texel = |x| img.get_pixel(x, 0).0;
+    assert_eq!(texel(0), [68, 1, 84, 255]);
+    assert_eq!(texel(255), [253, 231, 37, 255]);
+}
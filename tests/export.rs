@@ -0,0 +1,112 @@
+use hello_world::colormap::Colormap;
+use hello_world::mesh::NormalMode;
+use hello_world::ply::{self, PlyFormat};
+use hello_world::{math_func, obj, surface_data, vertex_data};
+
+fn seashell() -> hello_world::mesh::Mesh {
+    surface_data::parametric_surface_data(
+        &math_func::seashell,
+        &Colormap::from_name("cool").unwrap(),
+        0.0,
+        6.0 * std::f32::consts::PI,
+        0.0,
+        2.0 * std::f32::consts::PI,
+        12,
+        10,
+        -1.5,
+        1.5,
+        -1.5,
+        1.5,
+        1.5,
+        0.0,
+        NormalMode::AreaWeighted,
+    )
+}
+
+#[test]
+fn obj_writes_every_attribute() {
+    let mesh = seashell();
+    let mut out = Vec::new();
+    obj::write_obj(&mesh, &mut out, Some("seashell.mtl")).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    let count = |prefix: &str| text.lines().filter(|l| l.starts_with(prefix)).count();
+    assert_eq!(count("v "), mesh.num_vertices());
+    assert_eq!(count("vt "), mesh.num_vertices());
+    assert_eq!(count("vn "), mesh.num_vertices());
+    assert_eq!(count("f "), mesh.num_elements() / 3);
+    assert!(text.contains("mtllib seashell.mtl\n"));
+    // colored vertices carry six values, faces reference position/uv/normal
+    assert_eq!(
+        text.lines()
+            .find(|l| l.starts_with("v "))
+            .unwrap()
+            .split(' ')
+            .count(),
+        7
+    );
+    assert!(text.contains("\nf 1/1/1 2/2/2 3/3/3\n"));
+
+    // an indexed mesh without colors or uvs
+    let mut sphere = vertex_data::sphere_data_index(1.0, 5, 6);
+    sphere.uvs.clear();
+    let mut out = Vec::new();
+    obj::write_obj(&sphere, &mut out, None).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    let first_face = text.lines().find(|l| l.starts_with("f ")).unwrap();
+    let [a, b, c] = sphere.triangles()[0];
+    assert_eq!(
+        first_face,
+        format!(
+            "f {}//{} {}//{} {}//{}",
+            a + 1,
+            a + 1,
+            b + 1,
+            b + 1,
+            c + 1,
+            c + 1
+        )
+    );
+    assert!(!text.contains("usemtl"));
+}
+
+#[test]
+fn ply_ascii_and_binary_agree() {
+    let mesh = seashell();
+    let mut ascii = Vec::new();
+    ply::write_ply(&mesh, &mut ascii, PlyFormat::Ascii).unwrap();
+    let mut binary = Vec::new();
+    ply::write_ply(&mesh, &mut binary, PlyFormat::BinaryLittleEndian).unwrap();
+
+    let header_end = |data: &[u8]| {
+        let marker = b"end_header\n";
+        data.windows(marker.len())
+            .position(|w| w == marker)
+            .unwrap()
+            + marker.len()
+    };
+    let ascii_header = std::str::from_utf8(&ascii[..header_end(&ascii)]).unwrap();
+    let binary_header = std::str::from_utf8(&binary[..header_end(&binary)]).unwrap();
+    assert_eq!(
+        ascii_header.replace("format ascii 1.0", "format binary_little_endian 1.0"),
+        binary_header
+    );
+    assert!(ascii_header.contains(&format!("element vertex {}\n", mesh.num_vertices())));
+    assert!(ascii_header.contains("property uchar red\n"));
+    assert!(ascii_header.contains("property float s\n"));
+
+    // x y z nx ny nz (f32), r g b (u8), s t (f32) per vertex; count + three u32 per face
+    let faces = mesh.num_elements() / 3;
+    let body = binary.len() - header_end(&binary);
+    assert_eq!(
+        body,
+        mesh.num_vertices() * (6 * 4 + 3 + 2 * 4) + faces * (1 + 3 * 4)
+    );
+
+    let ascii_body = std::str::from_utf8(&ascii[header_end(&ascii)..]).unwrap();
+    let lines: Vec<&str> = ascii_body.lines().collect();
+    assert_eq!(lines.len(), mesh.num_vertices() + faces);
+    let first: Vec<f32> = lines[0].split(' ').map(|f| f.parse().unwrap()).collect();
+    assert_eq!(first[..3], mesh.positions[0]);
+    assert_eq!(lines[mesh.num_vertices()], "3 0 1 2");
+}
@@ -0,0 +1,72 @@
+use hello_world::expr::{self, Expr};
+use hello_world::math_func;
+
+fn eval(text: &str, x: f32, z: f32) -> f32 {
+    Expr::parse(text, ["x", "z"]).unwrap().eval(x, z)
+}
+
+fn error(text: &str) -> String {
+    format!("{:#}", Expr::parse(text, ["x", "z"]).unwrap_err())
+}
+
+#[test]
+fn precedence_and_functions() {
+    assert_eq!(eval("1 + 2*3 - 4/2", 0.0, 0.0), 5.0);
+    assert_eq!(eval("-x^2", 3.0, 0.0), -9.0);
+    assert_eq!(eval("2^3^2", 0.0, 0.0), 512.0);
+    assert_eq!(eval("2**-1", 0.0, 0.0), 0.5);
+    assert_eq!(eval("(x + z) * .5e1", 1.0, 2.0), 15.0);
+    assert_eq!(eval("max(x, z) + min(x, z)", 1.0, 2.0), 3.0);
+    assert!((eval("atan2(z, x) - pi/4", 1.0, 1.0)).abs() < 1e-6);
+    assert!((eval("ln(e) + cos(tau)", 0.0, 0.0) - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn formulas_match_the_built_in_surfaces() {
+    let sinc = expr::simple_surface("sin(sqrt(x*x+z*z))/sqrt(x*x+z*z)").unwrap();
+    let peaks = expr::simple_surface(
+        "3*(1-x)^2*exp(-x^2-(z+1)^2) - 10*(x/5-x^3-z^5)*exp(-x^2-z^2) - 1/3*exp(-(x+1)^2-z^2)",
+    )
+    .unwrap();
+    let wellenkugel = expr::parametric_surface(
+        "u*cos(cos(u))*sin(v)",
+        "u*sin(cos(u))",
+        "u*cos(cos(u))*cos(v)",
+    )
+    .unwrap();
+    for (a, b) in [(0.5, -1.0), (2.0, 3.0), (-2.5, 0.25)] {
+        for (p, q) in [
+            (sinc(a, b), math_func::sinc(a, b)),
+            (peaks(a, b), math_func::peaks(a, b)),
+            (wellenkugel(a, b), math_func::wellenkugel(a, b)),
+        ] {
+            assert!(
+                (0..3).all(|k| (p[k] - q[k]).abs() < 1e-4),
+                "{:?} != {:?}",
+                p,
+                q
+            );
+        }
+    }
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    assert_eq!(
+        error("sin(x) + y"),
+        "unknown variable 'y', expected x or z \
+        (or one of the constants pi, e, tau) at column 10\n  sin(x) + y\n           ^"
+    );
+    assert!(error("sni(x)").starts_with("unknown function 'sni' at column 1\n  sni(x)\n  ^^^"));
+    assert!(error("pow(x)").starts_with("function 'pow' takes 2 arguments, found 1"));
+    assert!(error("sqrt x").starts_with("function 'sqrt' needs its arguments in parentheses"));
+    assert!(error("(x + 1").starts_with("expected ')', found end of input at column 7"));
+    assert!(error("x z").starts_with("expected an operator, found 'z' at column 3"));
+    let expected = "expected a number, variable, function or '(', found '*'";
+    assert!(error("x + * z").starts_with(expected));
+    assert!(error("x # 2").starts_with("unexpected character '#' at column 3"));
+    assert!(error("1.2.3").starts_with("invalid number '1.2.3'"));
+
+    let e = expr::parametric_surface("u", "v +", "0").err().unwrap();
+    assert!(format!("{:#}", e).starts_with("invalid formula for y: expected a number"));
+}
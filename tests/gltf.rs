@@ -0,0 +1,130 @@
+use hello_world::colormap::Colormap;
+use hello_world::gltf::{self, BaseColorTexture, Material};
+use hello_world::mesh::NormalMode;
+use hello_world::surface_data::Domain;
+use hello_world::{math_func, surface_data, vertex_data};
+
+// split a glb file into its json document and binary chunk
+fn read_glb(data: &[u8]) -> (serde_json::Value, Vec<u8>) {
+    let word = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    assert_eq!(&data[0..4], b"glTF");
+    assert_eq!(word(4), 2);
+    assert_eq!(word(8) as usize, data.len());
+
+    let json_length = word(12) as usize;
+    assert_eq!(&data[16..20], b"JSON");
+    assert_eq!(json_length % 4, 0);
+    let doc = serde_json::from_slice(&data[20..20 + json_length]).unwrap();
+
+    let bin_start = 20 + json_length;
+    let bin_length = word(bin_start) as usize;
+    assert_eq!(&data[bin_start + 4..bin_start + 8], b"BIN\0");
+    assert_eq!(bin_start + 8 + bin_length, data.len());
+    (doc, data[bin_start + 8..].to_vec())
+}
+
+#[test]
+fn glb_holds_the_mesh_attributes() {
+    let jet = Colormap::default();
+    let mesh = surface_data::surface_data_index(
+        &math_func::peaks,
+        &jet,
+        &Domain::Simple {
+            x: [-3.0, 3.0],
+            z: [-3.0, 3.0],
+        },
+        [11, 11],
+        2.0,
+        0.0,
+        NormalMode::Analytic,
+    );
+    let mut out = Vec::new();
+    gltf::write_glb(&mesh, &mut out, &Material::default(), None).unwrap();
+    let (doc, bin) = read_glb(&out);
+
+    assert_eq!(
+        doc["buffers"][0]["byteLength"].as_u64().unwrap() as usize,
+        bin.len()
+    );
+    let primitive = &doc["meshes"][0]["primitives"][0];
+    let accessor =
+        |name: &str| &doc["accessors"][primitive["attributes"][name].as_u64().unwrap() as usize];
+    for name in ["POSITION", "NORMAL", "TEXCOORD_0", "COLOR_0"] {
+        assert_eq!(accessor(name)["count"], mesh.num_vertices());
+    }
+    assert_eq!(accessor("POSITION")["min"].as_array().unwrap().len(), 3);
+
+    // indices fit in u16 here; read them back through their buffer view
+    let indices = &doc["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+    assert_eq!(indices["componentType"], 5123);
+    assert_eq!(indices["count"], mesh.num_elements());
+    let view = &doc["bufferViews"][indices["bufferView"].as_u64().unwrap() as usize];
+    let offset = view["byteOffset"].as_u64().unwrap() as usize;
+    let first: Vec<u32> = bin[offset..offset + 6]
+        .chunks(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
+        .collect();
+    assert_eq!(first, mesh.indices.as_ref().unwrap()[..3]);
+    assert!(doc.get("images").is_none());
+}
+
+#[test]
+fn glb_embeds_the_texture_and_material() {
+    let image = image::open("examples/ch10/assets/bricks.png")
+        .unwrap()
+        .to_rgba8();
+    let texture = BaseColorTexture {
+        image: &image,
+        address_modes: [wgpu::AddressMode::Repeat, wgpu::AddressMode::ClampToEdge],
+    };
+    let material = Material::from_blinn_phong(0.8, 0.4, [1.0, 1.0, 0.0], 30.0, true);
+    assert!((material.roughness - 0.5).abs() < 1e-6);
+
+    let mesh = vertex_data::sphere_data(1.5, 15, 20);
+    let mut out = Vec::new();
+    gltf::write_glb(&mesh, &mut out, &material, Some(texture)).unwrap();
+    let (doc, bin) = read_glb(&out);
+
+    let material_doc = &doc["materials"][0];
+    assert_eq!(material_doc["doubleSided"], true);
+    assert_eq!(
+        material_doc["pbrMetallicRoughness"]["baseColorTexture"]["index"],
+        0
+    );
+    assert_eq!(
+        material_doc["extensions"]["KHR_materials_specular"]["specularColorFactor"],
+        serde_json::json!([1.0, 1.0, 0.0])
+    );
+    assert_eq!(doc["samplers"][0]["wrapS"], 10497);
+    assert_eq!(doc["samplers"][0]["wrapT"], 33071);
+    assert!(doc["meshes"][0]["primitives"][0].get("indices").is_none());
+
+    let view = &doc["bufferViews"][doc["images"][0]["bufferView"].as_u64().unwrap() as usize];
+    let offset = view["byteOffset"].as_u64().unwrap() as usize;
+    let length = view["byteLength"].as_u64().unwrap() as usize;
+    let img = image::load_from_memory(&bin[offset..offset + length]).unwrap();
+    assert_eq!(img.to_rgba8(), image);
+}
+
+#[test]
+fn blinn_phong_maps_to_pbr() {
+    let material = Material::from_blinn_phong(0.6, 0.3, [0.5, 0.25, 1.0], 2.0, false);
+    assert_eq!(material.base_color, [0.6, 0.6, 0.6, 1.0]);
+    assert_eq!(material.metallic, 0.0);
+    assert!((material.roughness - 0.5f32.sqrt().sqrt()).abs() < 1e-6);
+    assert_eq!(material.specular, 0.3);
+    assert_eq!(material.specular_color, [0.5, 0.25, 1.0]);
+    assert!(!material.double_sided);
+
+    // a dull highlight is fully rough, sharper highlights are smoother
+    let rough = |n| Material::from_blinn_phong(0.8, 0.4, [1.0; 3], n, false).roughness;
+    assert_eq!(rough(0.0), 1.0);
+    assert_eq!(rough(-5.0), 1.0);
+    assert!(rough(10.0) > rough(100.0) && rough(100.0) > rough(1000.0));
+    assert!(rough(1000.0) < 0.3);
+
+    // intensities outside [0, 1] are clamped
+    let bright = Material::from_blinn_phong(2.0, -1.0, [1.0; 3], 30.0, true);
+    assert_eq!(bright.base_color, [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(bright.specular, 0.0);
+}
@@ -0,0 +1,479 @@
+// golden-image regression tests: every example scene is rendered headlessly into an offscreen
+// texture and compared against the reference images in tests/golden/.
+//
+// the references are rendered on a software rasterizer; run the suite the same way, e.g.
+//     WGPU_BACKEND=gl WGPU_FORCE_FALLBACK_ADAPTER=1 cargo test --test golden
+// (llvmpipe through GL, or WGPU_BACKEND=vulkan for lavapipe). set UPDATE_GOLDEN=1 to rewrite
+// the references after an intended visual change. on failure the rendered image and a diff
+// image are written to the cargo target tmp directory.
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use hello_world::colorbar::{Colorbar, ColorbarPosition};
+use hello_world::procedural::Procedural;
+use hello_world::{capture, math_func, transforms};
+
+#[path = "../examples/ch02/common.rs"]
+#[allow(dead_code)]
+mod ch02;
+#[path = "../examples/ch04/square_index.rs"]
+#[allow(dead_code)]
+mod ch04_square_index;
+// the other ch10 examples declare common.rs as a module of their own too, so only the surface
+// viewer is included and the textured scenes are built from its copy of common.rs
+#[path = "../examples/ch10/surface_viewer.rs"]
+#[allow(dead_code)]
+mod ch10_surface_viewer;
+use ch10_surface_viewer::common as ch10_common;
+#[path = "../examples/ch13/attractors.rs"]
+mod ch13_attractors;
+#[path = "../examples/ch13/boids.rs"]
+mod ch13_boids;
+#[path = "../examples/ch13/particles.rs"]
+mod ch13_particles;
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+// largest per-channel difference that still counts as a matching pixel
+const TOLERANCE: u8 = 8;
+// fraction of pixels allowed to exceed TOLERANCE, to absorb rasterization differences on edges
+const MAX_MISMATCH_FRACTION: f64 = 0.002;
+
+// compute steps the particle systems are advanced before capture
+const SIMULATION_STEPS: u32 = 30;
+
+// software adapters do not like several devices being created concurrently
+static GPU_LOCK: Mutex<()> = Mutex::new(());
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.png", name))
+}
+
+fn output_path(name: &str, suffix: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}_{}.png", name, suffix))
+}
+
+fn check_golden(name: &str, img: &image::RgbaImage) {
+    let reference_file = golden_path(name);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        img.save(&reference_file).unwrap();
+        return;
+    }
+
+    let reference = match image::open(&reference_file) {
+        Ok(reference) => reference.to_rgba8(),
+        Err(e) => panic!(
+            "cannot read reference image {:?} ({}); run with UPDATE_GOLDEN=1 to create it",
+            reference_file, e
+        ),
+    };
+    assert_eq!(
+        reference.dimensions(),
+        img.dimensions(),
+        "{}: image size does not match the reference",
+        name
+    );
+
+    // mismatching pixels are drawn in red on top of a faded copy of the reference
+    let mut diff = image::RgbaImage::new(img.width(), img.height());
+    let mut mismatches = 0usize;
+    for (x, y, expected) in reference.enumerate_pixels() {
+        let actual = img.get_pixel(x, y);
+        let max_delta = (0..4)
+            .map(|c| (expected[c] as i16 - actual[c] as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap();
+        if max_delta > TOLERANCE {
+            mismatches += 1;
+            diff.put_pixel(x, y, image::Rgba([255, 0, 0, 255]));
+        } else {
+            let faded = |c: u8| c / 4 + 96;
+            diff.put_pixel(
+                x,
+                y,
+                image::Rgba([
+                    faded(expected[0]),
+                    faded(expected[1]),
+                    faded(expected[2]),
+                    255,
+                ]),
+            );
+        }
+    }
+
+    let allowed = (MAX_MISMATCH_FRACTION * (img.width() * img.height()) as f64) as usize;
+    if mismatches > allowed {
+        let actual_file = output_path(name, "actual");
+        let diff_file = output_path(name, "diff");
+        img.save(&actual_file).unwrap();
+        diff.save(&diff_file).unwrap();
+        panic!(
+            "{}: {} pixels differ from the reference by more than {} (allowed {}); \
+             see {:?} and {:?}",
+            name, mismatches, TOLERANCE, allowed, actual_file, diff_file
+        );
+    }
+}
+
+fn render_ch02_scene(
+    source: &'static str,
+    topology: wgpu::PrimitiveTopology,
+    num_vertices: u32,
+) -> image::RgbaImage {
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let strip_index_format = match topology {
+        wgpu::PrimitiveTopology::LineStrip | wgpu::PrimitiveTopology::TriangleStrip => {
+            Some(wgpu::IndexFormat::Uint32)
+        }
+        _ => None,
+    };
+    let inputs = ch02::Inputs {
+        source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+        topology,
+        strip_index_format,
+    };
+    let pipeline = ch02::create_pipeline(&init.device, init.config.format, inputs);
+
+    let frame = init.get_current_frame().unwrap();
+    let view = frame
+        .texture()
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    ch02::draw(&init.device, &init.queue, &view, &pipeline, num_vertices);
+    capture::capture_texture(
+        &init.device,
+        &init.queue,
+        frame.texture(),
+        WIDTH,
+        HEIGHT,
+        FORMAT,
+    )
+    .unwrap()
+}
+
+fn capture_offscreen(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    offscreen: &Option<wgpu::Texture>,
+) -> image::RgbaImage {
+    let texture = offscreen.as_ref().unwrap();
+    capture::capture_texture(device, queue, texture, WIDTH, HEIGHT, FORMAT).unwrap()
+}
+
+#[test]
+fn ch02_first_triangle() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch02_scene(
+        include_str!("../examples/ch02/first_triangle.wgsl"),
+        wgpu::PrimitiveTopology::TriangleList,
+        3,
+    );
+    check_golden("ch02_first_triangle", &img);
+}
+
+#[test]
+fn ch02_triangle_vertex_color() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch02_scene(
+        include_str!("../examples/ch02/triangle_vertex_color.wgsl"),
+        wgpu::PrimitiveTopology::TriangleList,
+        3,
+    );
+    check_golden("ch02_triangle_vertex_color", &img);
+}
+
+#[test]
+fn ch03_point_line() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, topology) in [
+        ("ch03_point_list", wgpu::PrimitiveTopology::PointList),
+        ("ch03_line_list", wgpu::PrimitiveTopology::LineList),
+        ("ch03_line_strip", wgpu::PrimitiveTopology::LineStrip),
+    ] {
+        let img = render_ch02_scene(
+            include_str!("../examples/ch03/point_line.wgsl"),
+            topology,
+            6,
+        );
+        check_golden(name, &img);
+    }
+}
+
+#[test]
+fn ch03_triangles() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, topology) in [
+        ("ch03_triangle_list", wgpu::PrimitiveTopology::TriangleList),
+        (
+            "ch03_triangle_strip",
+            wgpu::PrimitiveTopology::TriangleStrip,
+        ),
+    ] {
+        let img = render_ch02_scene(include_str!("../examples/ch03/triangles.wgsl"), topology, 9);
+        check_golden(name, &img);
+    }
+}
+
+#[test]
+fn ch04_square_index() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mut state = ch04_square_index::State::with_init(init);
+    state.render().unwrap();
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch04_square_index", &img);
+}
+
+fn render_ch10_scene(
+    vertex_data: &Vec<ch10_common::Vertex>,
+    texture: &str,
+    u_mode: wgpu::AddressMode,
+    v_mode: wgpu::AddressMode,
+) -> image::RgbaImage {
+    let light_data = ch10_common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let image = ch10_common::ImageSource::from_name(texture, "").unwrap();
+    let mut state = pollster::block_on(ch10_common::State::new_headless(
+        WIDTH,
+        HEIGHT,
+        vertex_data,
+        light_data,
+        &image,
+        u_mode,
+        v_mode,
+    ))
+    .unwrap();
+    state.update(std::time::Duration::from_millis(500));
+    state.render().unwrap();
+    capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen)
+}
+
+#[test]
+fn ch10_texture_cube() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&ch10_common::cube_mesh()),
+        "examples/ch10/assets/bricks.png",
+        wgpu::AddressMode::ClampToEdge,
+        wgpu::AddressMode::ClampToEdge,
+    );
+    check_golden("ch10_texture_cube", &img);
+}
+
+// procedural textures are told from files by name, and their errors are kept
+#[test]
+fn ch10_image_sources() {
+    use ch10_common::ImageSource;
+    let source = |name| ImageSource::from_name(name, "assets/");
+    assert_eq!(source("uv:4").unwrap(), ImageSource::Procedural(Procedural::UvGrid { cells: 4 }));
+    assert_eq!(source("uv.png").unwrap(), ImageSource::File("assets/uv.png".to_string()));
+    let error = source("checker:0").unwrap_err();
+    assert!(error.to_string().contains("checker:0"), "{}", error);
+}
+
+// F11 saves the object with the light's material and the texture image as a gltf file
+#[test]
+fn ch10_export_glb() {
+    let mesh = ch10_common::sphere_mesh();
+    let light_data = ch10_common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let image = ch10_common::ImageSource::from_name("uv:4", "").unwrap();
+    let modes = [wgpu::AddressMode::Repeat, wgpu::AddressMode::ClampToEdge];
+    let file_name = output_path("ch10_export", "saved").with_extension("glb");
+    ch10_common::export_glb(&mesh, &light_data, &image, modes, file_name.to_str().unwrap())
+        .unwrap();
+    let data = std::fs::read(&file_name).unwrap();
+    assert_eq!(&data[0..4], b"glTF");
+    let json_length = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+    let doc: serde_json::Value = serde_json::from_slice(&data[20..20 + json_length]).unwrap();
+    let pbr = &doc["materials"][0]["pbrMetallicRoughness"];
+    assert_eq!(pbr["baseColorFactor"], serde_json::json!([0.8f32, 0.8f32, 0.8f32, 1.0f32]));
+    assert_eq!(pbr["baseColorTexture"]["index"], 0);
+    assert_eq!(doc["materials"][0]["doubleSided"], true);
+
+    let missing = ch10_common::ImageSource::File("missing.png".to_string());
+    let error = ch10_common::export_glb(&mesh, &light_data, &missing, modes, "unused.glb");
+    assert!(format!("{:?}", error.unwrap_err()).contains("missing.png"));
+}
+
+// a frame requested with F12 is saved to a png file matching what was rendered, and a failed
+// save is returned by finish_frame
+#[test]
+fn ch10_requested_capture() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let light_data = ch10_common::light([1.0, 1.0, 0.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let image = ch10_common::ImageSource::from_name("checker", "").unwrap();
+    let mut state = pollster::block_on(ch10_common::State::new_headless(
+        WIDTH,
+        HEIGHT,
+        &ch10_common::vertices(&ch10_common::cube_mesh()),
+        light_data,
+        &image,
+        wgpu::AddressMode::ClampToEdge,
+        wgpu::AddressMode::ClampToEdge,
+    ))
+    .unwrap();
+    let file_name = output_path("ch10_requested_capture", "saved");
+    state.init.request_capture(file_name.to_str().unwrap());
+    state.render().unwrap();
+    let saved = image::open(&file_name).unwrap().to_rgba8();
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    assert_eq!(saved, img);
+
+    // a capture that cannot be saved is reported, and the next frame is not captured again
+    let missing = output_path("missing/ch10_requested_capture", "saved");
+    state.init.request_capture(missing.to_str().unwrap());
+    let frame = state.init.get_current_frame().unwrap();
+    let error = state.init.finish_frame(frame).unwrap_err();
+    assert!(format!("{:?}", error).contains("missing"), "{:?}", error);
+    let frame = state.init.get_current_frame().unwrap();
+    state.init.finish_frame(frame).unwrap();
+}
+
+#[test]
+fn ch10_obj_model() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mesh = ch10_common::model_mesh("examples/ch10/assets/cube.obj").unwrap();
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&mesh),
+        "examples/ch10/assets/bricks.png",
+        wgpu::AddressMode::Repeat,
+        wgpu::AddressMode::Repeat,
+    );
+    check_golden("ch10_obj_model", &img);
+}
+
+#[test]
+fn ch10_texture_sphere() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&ch10_common::sphere_mesh()),
+        "examples/ch10/assets/bricks.png",
+        wgpu::AddressMode::Repeat,
+        wgpu::AddressMode::ClampToEdge,
+    );
+    check_golden("ch10_texture_sphere", &img);
+}
+
+// a procedural uv grid shows how the sphere's texture coordinates wrap
+#[test]
+fn ch10_texture_sphere_uv_grid() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&ch10_common::sphere_mesh()),
+        "uv:8",
+        wgpu::AddressMode::Repeat,
+        wgpu::AddressMode::ClampToEdge,
+    );
+    check_golden("ch10_texture_sphere_uv_grid", &img);
+}
+
+#[test]
+fn ch10_texture_torus() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&ch10_common::torus_mesh()),
+        "examples/ch10/assets/bricks.png",
+        wgpu::AddressMode::Repeat,
+        wgpu::AddressMode::Repeat,
+    );
+    check_golden("ch10_texture_torus", &img);
+}
+
+#[test]
+fn ch10_texture_surface() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let img = render_ch10_scene(
+        &ch10_common::vertices(&ch10_common::surface_mesh(&math_func::sinc)),
+        "examples/ch10/assets/bricks.png",
+        wgpu::AddressMode::ClampToEdge,
+        wgpu::AddressMode::ClampToEdge,
+    );
+    check_golden("ch10_texture_surface", &img);
+}
+
+#[test]
+fn ch10_surface_viewer() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mesh = ch10_surface_viewer::create_mesh("sinc").unwrap();
+    let light_data = ch10_common::light([1.0, 1.0, 1.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let mut state = ch10_surface_viewer::State::with_init(init, &mesh, light_data, "jet").unwrap();
+    state.show_wireframe = true;
+    state.update(std::time::Duration::ZERO);
+    state.render().unwrap();
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch10_surface_viewer", &img);
+}
+
+// the colormap, value range and bands changed after the mesh was uploaded, with a colorbar
+#[test]
+fn ch10_surface_viewer_banded() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mesh = ch10_surface_viewer::create_mesh("peaks").unwrap();
+    let light_data = ch10_common::light([1.0, 1.0, 1.0], 0.1, 0.8, 0.4, 30.0, 1);
+    let mut state = ch10_surface_viewer::State::with_init(init, &mesh, light_data, "jet").unwrap();
+    state.set_colormap("viridis").unwrap();
+    assert!(state.set_colormap("virdis").is_err());
+    let [lo, hi] = state.value_range;
+    state.value_range = [lo + 0.2 * (hi - lo), hi - 0.2 * (hi - lo)];
+    state.bands = 8;
+    state.colorbar = Some(Colorbar::new(ColorbarPosition::Right));
+    state.update(std::time::Duration::ZERO);
+    state.render().unwrap();
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch10_surface_viewer_banded", &img);
+}
+
+#[test]
+fn ch13_particles() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mut state = ch13_particles::State::with_init(init, 10000, 2.0, 42);
+    for i in 1..=SIMULATION_STEPS {
+        state.step(i as f32 / 60.0);
+        state.render().unwrap();
+    }
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch13_particles", &img);
+}
+
+// the particle view follows the window size: one unit per pixel, without stretching
+#[test]
+fn ch13_particles_resize() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mut state = ch13_particles::State::with_init(init, 100, 2.0, 42);
+    state.resize(winit::dpi::PhysicalSize::new(2 * WIDTH, HEIGHT / 2));
+    let corner = cgmath::Vector4::new(WIDTH as f32, HEIGHT as f32 / 4.0, 0.0, 1.0);
+    let clip = state.projection() * corner;
+    assert!((clip.x - 1.0).abs() < 1e-5 && (clip.y - 1.0).abs() < 1e-5, "{:?}", clip);
+}
+
+#[test]
+fn ch13_attractors() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mut state =
+        ch13_attractors::State::with_init(init, 5000, 2.0, 0.5, vec![10.0, 10.0, 10.0], 42);
+    for _ in 0..SIMULATION_STEPS {
+        state.render().unwrap();
+    }
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch13_attractors", &img);
+}
+
+#[test]
+fn ch13_boids() {
+    let _lock = GPU_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(WIDTH, HEIGHT, FORMAT));
+    let mut state = ch13_boids::State::with_init(init, 0.1, 42);
+    for _ in 0..SIMULATION_STEPS {
+        state.render().unwrap();
+    }
+    let img = capture_offscreen(&state.init.device, &state.init.queue, &state.init.offscreen);
+    check_golden("ch13_boids", &img);
+}
@@ -0,0 +1,265 @@
+use hello_world::colormap::Colormap;
+use hello_world::mesh::{grid_indices, NormalMode};
+use hello_world::surface_data::Domain;
+use hello_world::{math_func, surface_data, vertex_data};
+
+#[test]
+fn generators_fill_matching_attributes() {
+    let jet = Colormap::default();
+    let sphere = vertex_data::sphere_data(1.5, 10, 15);
+    assert_eq!(sphere.num_vertices(), 6 * 9 * 14);
+    assert_eq!(sphere.normals.len(), sphere.num_vertices());
+
+    let surface = surface_data::simple_surface_data(
+        &math_func::peaks,
+        &jet,
+        -3.0,
+        3.0,
+        -3.0,
+        3.0,
+        11,
+        11,
+        2.0,
+        0.0,
+        NormalMode::Flat,
+    );
+    assert_eq!(surface.num_vertices(), 6 * 10 * 10);
+    assert_eq!(surface.normals.len(), surface.num_vertices());
+    assert_eq!(surface.colors.len(), surface.num_vertices());
+    assert!(surface.indices.is_none());
+}
+
+#[test]
+fn generators_fill_uvs_over_the_domain() {
+    let jet = Colormap::default();
+    let meshes = [
+        vertex_data::sphere_data(1.5, 10, 15),
+        vertex_data::cone_data(0.5, 1.0, 2.0, 8),
+        vertex_data::torus_data(1.5, 0.4, 20, 12, NormalMode::Flat),
+        surface_data::parametric_surface_data(
+            &math_func::klein_bottle,
+            &jet,
+            0.0,
+            std::f32::consts::PI,
+            0.0,
+            2.0 * std::f32::consts::PI,
+            20,
+            20,
+            -2.0,
+            2.0,
+            -2.0,
+            2.0,
+            1.5,
+            0.0,
+            NormalMode::Flat,
+        ),
+        vertex_data::sphere_data_index(1.5, 10, 15),
+        vertex_data::cone_data_index(0.5, 1.0, 2.0, 8),
+        vertex_data::torus_data_index(1.5, 0.4, 20, 12),
+        surface_data::surface_data_index(
+            &math_func::peaks,
+            &jet,
+            &Domain::Simple {
+                x: [-3.0, 3.0],
+                z: [-3.0, 3.0],
+            },
+            [11, 11],
+            2.0,
+            0.0,
+            NormalMode::Flat,
+        ),
+    ];
+    for mesh in &meshes {
+        assert_eq!(mesh.uvs.len(), mesh.num_vertices());
+        let (mut min, mut max) = ([f32::MAX; 2], [f32::MIN; 2]);
+        for uv in &mesh.uvs {
+            for k in 0..2 {
+                min[k] = min[k].min(uv[k]);
+                max[k] = max[k].max(uv[k]);
+            }
+        }
+        for k in 0..2 {
+            assert!(
+                min[k].abs() < 1e-5 && (max[k] - 1.0).abs() < 1e-5,
+                "{:?} {:?}",
+                min,
+                max
+            );
+        }
+    }
+}
+
+#[test]
+fn interleave_fills_missing_attributes() {
+    let cone = vertex_data::cone_data(0.5, 1.0, 2.0, 8);
+    let vertices = cone.vertices();
+    assert_eq!(vertices.len(), cone.num_elements());
+    assert_eq!(vertices[0].position, cone.positions[0]);
+    assert_eq!(vertices[0].color, [1.0, 1.0, 1.0]);
+    assert_eq!(vertices[0].uv, cone.uvs[0]);
+    let untextured = hello_world::mesh::Mesh {
+        uvs: Vec::new(),
+        ..cone.clone()
+    };
+    assert_eq!(untextured.vertices()[0].uv, [0.0, 0.0]);
+    assert_eq!(
+        bytemuck::cast_slice::<_, u8>(&vertices).len(),
+        vertices.len() * 11 * 4
+    );
+}
+
+#[test]
+fn indexed_generators_share_vertices() {
+    let sphere = vertex_data::sphere_data_index(1.5, 10, 15);
+    assert_eq!(sphere.num_vertices(), 10 * 15);
+    assert_eq!(sphere.num_elements(), 6 * 9 * 14);
+    let torus = vertex_data::torus_data_index(1.5, 0.4, 20, 12);
+    assert_eq!(torus.num_vertices(), 20 * 12);
+    let cone = vertex_data::cone_data_index(0.5, 1.0, 2.0, 8);
+    assert_eq!(
+        cone.num_elements(),
+        vertex_data::cone_data(0.5, 1.0, 2.0, 8).num_vertices()
+    );
+
+    // every indexed triangle faces the same way as its vertex normals
+    for mesh in [&sphere, &torus, &cone] {
+        for tri in mesh.indices.as_ref().unwrap().chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| mesh.positions[tri[k] as usize]);
+            let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let face = [
+                e1[1] * e2[2] - e1[2] * e2[1],
+                e1[2] * e2[0] - e1[0] * e2[2],
+                e1[0] * e2[1] - e1[1] * e2[0],
+            ];
+            let n = mesh.normals[tri[0] as usize];
+            assert!(face[0] * n[0] + face[1] * n[1] + face[2] * n[2] >= -1e-6);
+        }
+    }
+}
+
+#[test]
+fn degenerate_grids_have_no_triangles() {
+    let corners = [(0, 0), (1, 0), (1, 1), (0, 1)];
+    assert!(grid_indices(0, 5, corners).is_empty());
+    assert!(grid_indices(5, 1, corners).is_empty());
+    assert_eq!(grid_indices(2, 2, corners), [0, 2, 3, 3, 1, 0]);
+    assert_eq!(vertex_data::sphere_data_index(1.0, 0, 0).num_elements(), 0);
+    assert_eq!(
+        vertex_data::cone_data_index(0.5, 1.0, 2.0, 0).num_elements(),
+        0
+    );
+}
+
+#[test]
+fn index_width_follows_vertex_count() {
+    let jet = Colormap::default();
+    let small = surface_data::surface_data_index(
+        &math_func::peaks,
+        &jet,
+        &Domain::Simple {
+            x: [-3.0, 3.0],
+            z: [-3.0, 3.0],
+        },
+        [11, 11],
+        2.0,
+        0.0,
+        NormalMode::Flat,
+    );
+    assert_eq!(small.num_vertices(), 11 * 11);
+    assert_eq!(small.colors.len(), small.num_vertices());
+    let data = small.index_data().unwrap();
+    assert_eq!(data.format(), wgpu::IndexFormat::Uint16);
+    assert_eq!(data.len(), 6 * 10 * 10);
+
+    let klein = Domain::Parametric {
+        u: [0.0, std::f32::consts::PI],
+        v: [0.0, 2.0 * std::f32::consts::PI],
+        x: [-2.0, 2.0],
+        z: [-2.0, 2.0],
+    };
+    let large = surface_data::surface_data_index(
+        &math_func::klein_bottle,
+        &jet,
+        &klein,
+        [300, 300],
+        1.5,
+        0.0,
+        NormalMode::AreaWeighted,
+    );
+    let data = large.index_data().unwrap();
+    assert_eq!(data.format(), wgpu::IndexFormat::Uint32);
+    assert_eq!(data.contents().len(), 4 * 6 * 299 * 299);
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[test]
+fn smooth_normals_agree_with_analytic() {
+    let jet = Colormap::default();
+    let surface = |mode| {
+        surface_data::simple_surface_data(
+            &math_func::peaks,
+            &jet,
+            -3.0,
+            3.0,
+            -3.0,
+            3.0,
+            41,
+            41,
+            2.0,
+            0.0,
+            mode,
+        )
+    };
+    let analytic = surface(NormalMode::Analytic);
+    for mode in [NormalMode::AreaWeighted, NormalMode::AngleWeighted] {
+        let averaged = surface(mode);
+        assert_eq!(averaged.positions, analytic.positions);
+        for (a, b) in averaged.normals.iter().zip(&analytic.normals) {
+            assert!(dot(*a, *b) > 0.95, "{:?}: {:?} vs {:?}", mode, a, b);
+        }
+    }
+
+    // vertices shared between quads get one normal, unlike the flat mode
+    let flat = surface(NormalMode::Flat);
+    let smooth = surface(NormalMode::AreaWeighted);
+    assert_ne!(flat.normals[1], flat.normals[6]);
+    assert_eq!(smooth.normals[1], smooth.normals[6]);
+
+    // the averaged torus normals also wrap around the seams
+    let exact = vertex_data::torus_data(1.5, 0.4, 40, 24, NormalMode::Analytic);
+    let averaged = vertex_data::torus_data(1.5, 0.4, 40, 24, NormalMode::AngleWeighted);
+    for (a, b) in averaged.normals.iter().zip(&exact.normals) {
+        assert!(dot(*a, *b) > 0.99);
+    }
+}
+
+#[test]
+fn edges_are_listed_once() {
+    let jet = Colormap::default();
+    // a 3 x 3 vertex grid: 12 grid lines and 4 diagonals
+    let mesh = surface_data::surface_data_index(
+        &math_func::peaks,
+        &jet,
+        &Domain::Simple {
+            x: [-3.0, 3.0],
+            z: [-3.0, 3.0],
+        },
+        [3, 3],
+        2.0,
+        0.0,
+        NormalMode::AreaWeighted,
+    );
+    let edges = mesh.edges();
+    assert_eq!(edges.len(), 16);
+    let mut keys: Vec<(u32, u32)> = edges.iter().map(|&[a, b]| (a.min(b), a.max(b))).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    assert_eq!(keys.len(), 16);
+
+    // without indices no vertex is shared, so every triangle keeps its three edges
+    assert_eq!(mesh.unindexed().edges().len(), 3 * 8);
+}
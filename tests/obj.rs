@@ -0,0 +1,85 @@
+use hello_world::colormap::Colormap;
+use hello_world::mesh::NormalMode;
+use hello_world::surface_data::Domain;
+use hello_world::{math_func, obj, surface_data};
+
+fn parse(text: &str) -> anyhow::Result<hello_world::mesh::Mesh> {
+    obj::parse_obj(text.as_bytes())
+}
+
+#[test]
+fn written_meshes_load_back() {
+    let jet = Colormap::default();
+    let mesh = surface_data::surface_data_index(
+        &math_func::peaks,
+        &jet,
+        &Domain::Simple {
+            x: [-3.0, 3.0],
+            z: [-3.0, 3.0],
+        },
+        [9, 9],
+        2.0,
+        0.0,
+        NormalMode::AreaWeighted,
+    );
+    let mut out = Vec::new();
+    obj::write_obj(&mesh, &mut out, None).unwrap();
+    let loaded = obj::parse_obj(out.as_slice()).unwrap();
+    assert_eq!(loaded.num_vertices(), mesh.num_vertices());
+
+    // the loader numbers vertices in order of first use, so compare the expanded triangles
+    let (loaded, mesh) = (loaded.unindexed(), mesh.unindexed());
+    assert_eq!(loaded.num_vertices(), mesh.num_vertices());
+    for (a, b) in [
+        (&loaded.positions, &mesh.positions),
+        (&loaded.normals, &mesh.normals),
+        (&loaded.colors, &mesh.colors),
+    ] {
+        for (p, q) in a.iter().zip(b.iter()) {
+            assert!((0..3).all(|k| (p[k] - q[k]).abs() < 1e-5));
+        }
+    }
+    for (p, q) in loaded.uvs.iter().zip(&mesh.uvs) {
+        assert!((0..2).all(|k| (p[k] - q[k]).abs() < 1e-5));
+    }
+}
+
+#[test]
+fn polygons_are_triangulated_and_normals_computed() {
+    // a quad and a pentagon in the y = 0 plane, facing up, with a negative index
+    let text = "v 0 0 0\nv 0 0 1\nv 1 0 1\nv 1 0 0\nv 2 0 1.5\nv 3 0 0.5\nv 2 0 -0.5\n\
+                f 1 2 3 4\nf 4 3 5 6 -1\n";
+    let mesh = parse(text).unwrap();
+    assert_eq!(mesh.num_vertices(), 7);
+    assert_eq!(
+        mesh.indices,
+        Some(vec![0, 1, 2, 0, 2, 3, 3, 2, 4, 3, 4, 5, 3, 5, 6])
+    );
+    assert!(mesh.uvs.is_empty() && mesh.colors.is_empty());
+    for n in &mesh.normals {
+        assert!((n[1] - 1.0).abs() < 1e-6);
+    }
+
+    // with "s off" the corners of each face get their own vertex and face normal
+    let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\ns off\nf 1 3 2\nf 1 2 4\n";
+    let mesh = parse(text).unwrap();
+    assert_eq!(mesh.num_vertices(), 6);
+    assert_eq!(mesh.normals[0], [0.0, 0.0, -1.0]);
+    assert_eq!(mesh.normals[3], [0.0, -1.0, 0.0]);
+    assert_eq!(mesh.unindexed().num_vertices(), 6);
+}
+
+#[test]
+fn errors_report_the_line() {
+    let err = parse("v 0 0 0\nv 1 0 0\nf 1 2 3\n").unwrap_err();
+    assert!(format!("{:?}", err).contains("line 3"), "{:?}", err);
+    assert!(
+        format!("{:?}", err).contains("vertex index 3 out of range"),
+        "{:?}",
+        err
+    );
+
+    let err = parse("v 0 zero 0\n").unwrap_err();
+    assert!(format!("{:?}", err).contains("line 1"), "{:?}", err);
+    assert!(parse("v 0 0 0\n").is_err());
+}
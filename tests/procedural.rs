@@ -0,0 +1,154 @@
+use hello_world::procedural::{fractal_noise, is_procedural_name, perlin, Procedural, MAX_OCTAVES};
+use hello_world::texture_data::{Texture, TextureOptions};
+use hello_world::{capture, transforms};
+
+#[test]
+fn procedural_names() {
+    assert_eq!(
+        Procedural::from_name("uv").unwrap(),
+        Procedural::UvGrid { cells: 8 }
+    );
+    assert_eq!(
+        Procedural::from_name("checker:4").unwrap(),
+        Procedural::Checkerboard { cells: 4 }
+    );
+    assert_eq!(
+        Procedural::from_name("noise:2:3").unwrap(),
+        Procedural::Noise {
+            cells: 2,
+            octaves: 3,
+            seed: 1
+        }
+    );
+    let gradient = Procedural::from_name("gradient:viridis_r:4").unwrap();
+    assert!(matches!(gradient, Procedural::Gradient(c) if c.reversed && c.bands == Some(4)));
+    let names = [
+        "bricks.png",
+        "checker:0",
+        "uv:4:4",
+        "noise:x",
+        "gradient:nope",
+        "uv:100000",
+    ];
+    for name in names.into_iter().chain(["noise:4:100000000"]) {
+        assert!(Procedural::from_name(name).is_err(), "{}", name);
+    }
+    assert!(Procedural::from_name("noise:4:16").is_ok());
+    for name in ["checker", "uv:4", "noise:x", "gradient:viridis"] {
+        assert!(is_procedural_name(name), "{}", name);
+    }
+    for name in ["bricks.png", "uvs.png", "noise.jpg", ""] {
+        assert!(!is_procedural_name(name), "{}", name);
+    }
+}
+
+#[test]
+fn noise_tiles_and_stays_in_range() {
+    // zero on the lattice, and the same on opposite edges of a tile
+    assert_eq!(perlin(3.0, 2.0, 4, 7), 0.0);
+    for k in 0..20 {
+        let t = k as f32 / 20.0;
+        let (a, b) = (
+            fractal_noise(0.0, t, 3, 4, 9),
+            fractal_noise(1.0, t, 3, 4, 9),
+        );
+        assert!((a - b).abs() < 1e-4, "{} {}", a, b);
+        let (a, b) = (
+            fractal_noise(t, 0.0, 3, 4, 9),
+            fractal_noise(t, 1.0, 3, 4, 9),
+        );
+        assert!((a - b).abs() < 1e-4, "{} {}", a, b);
+    }
+    let img = Procedural::Noise {
+        cells: 4,
+        octaves: 5,
+        seed: 3,
+    }
+    .image(64, 64);
+    let values: Vec<u8> = img.pixels().map(|p| p[0]).collect();
+    let (lo, hi) = (values.iter().min().unwrap(), values.iter().max().unwrap());
+    assert!(hi - lo > 100, "{} {}", lo, hi);
+    assert_ne!(
+        img,
+        Procedural::Noise {
+            cells: 4,
+            octaves: 5,
+            seed: 4
+        }
+        .image(64, 64)
+    );
+}
+
+#[test]
+fn procedural_textures() {
+    let checker = Procedural::Checkerboard { cells: 2 }.image(4, 4);
+    assert_eq!(checker.get_pixel(0, 0), checker.get_pixel(3, 3));
+    assert_ne!(checker.get_pixel(0, 0), checker.get_pixel(2, 0));
+
+    // u grows in red and v in green, inside the cells
+    let uv = Procedural::UvGrid { cells: 4 }.image(256, 256);
+    let (a, b, c) = (
+        uv.get_pixel(40, 60),
+        uv.get_pixel(168, 60),
+        uv.get_pixel(40, 168),
+    );
+    assert!(b[0] > a[0] && b[1] == a[1], "{:?} {:?}", a, b);
+    assert!(c[1] > a[1] && c[0] == a[0], "{:?} {:?}", a, c);
+
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(64, 64, format));
+    let gradient = Procedural::from_name("gradient:greys").unwrap();
+    let options = TextureOptions {
+        mipmaps: false,
+        readable: true,
+        ..Default::default()
+    };
+    let (device, queue) = (&init.device, &init.queue);
+    let texture =
+        Texture::create_procedural_texture(device, queue, &init.mipmaps, &gradient, 16, &options);
+    let img = capture::capture_mip_level(
+        &init.device,
+        &init.queue,
+        &texture.texture,
+        0,
+        16,
+        16,
+        format,
+    )
+    .unwrap();
+    assert_eq!(img, gradient.image(16, 16));
+    assert_eq!(img.get_pixel(0, 5)[0], 0);
+    assert_eq!(img.get_pixel(15, 5)[0], 255);
+}
+
+#[test]
+fn large_cell_counts_are_clamped() {
+    // more cells than pixels, and more than fit in the pixel arithmetic
+    for cells in [1000, u32::MAX] {
+        let uv = Procedural::UvGrid { cells }.image(512, 512);
+        assert_eq!(uv, Procedural::UvGrid { cells: 256 }.image(512, 512));
+        let checker = Procedural::Checkerboard { cells }.image(600, 600);
+        assert_eq!(
+            checker,
+            Procedural::Checkerboard { cells: 256 }.image(600, 600)
+        );
+    }
+}
+
+#[test]
+fn large_octave_counts_are_clamped() {
+    // fractal_noise stops at MAX_OCTAVES instead of looping for ever
+    let clamped = fractal_noise(0.3, 0.7, 4, MAX_OCTAVES, 2);
+    assert_eq!(fractal_noise(0.3, 0.7, 4, u32::MAX, 2), clamped);
+    let noise = Procedural::Noise {
+        cells: 4,
+        octaves: u32::MAX,
+        seed: 2,
+    };
+    let capped = Procedural::Noise {
+        cells: 4,
+        octaves: MAX_OCTAVES,
+        seed: 2,
+    };
+    assert_eq!(noise.image(8, 8), capped.image(8, 8));
+}
@@ -0,0 +1,63 @@
+use cgmath::{Deg, Matrix4, Point3, Transform};
+use hello_world::transforms::{self, Projection};
+
+// depth of a point at distance d in front of the camera
+fn depth(m: Matrix4<f32>, d: f32) -> f32 {
+    m.transform_point(Point3::new(0.0, 0.0, -d)).z
+}
+
+#[test]
+fn matches_the_fixed_projections() {
+    for is_perspective in [true, false] {
+        assert_eq!(
+            Projection::new(4.0 / 3.0, is_perspective).matrix(),
+            transforms::create_projection(4.0 / 3.0, is_perspective)
+        );
+    }
+    // the orthographic box used to be (-4, 4, -3, 3) at any aspect ratio
+    let ortho = Projection::orthographic(4.0 / 3.0).matrix();
+    let corner = ortho.transform_point(Point3::new(4.0, 3.0, 0.0));
+    assert!((corner.x - 1.0).abs() < 1e-6 && (corner.y - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn orthographic_extents_follow_the_aspect_ratio() {
+    let mut projection = Projection::orthographic(1.0);
+    projection.resize(1600, 400);
+    assert_eq!(projection.aspect, 4.0);
+    let corner = projection
+        .matrix()
+        .transform_point(Point3::new(12.0, 3.0, 0.0));
+    assert!((corner.x - 1.0).abs() < 1e-6 && (corner.y - 1.0).abs() < 1e-6);
+
+    // a zero-sized window keeps the previous aspect
+    projection.resize(0, 400);
+    assert_eq!(projection.aspect, 4.0);
+}
+
+#[test]
+fn reverse_z_swaps_near_and_far() {
+    let mut projection = Projection::perspective(1.5);
+    projection.fovy = Deg(45.0).into();
+    projection.near = 0.5;
+    projection.far = 50.0;
+    let m = projection.matrix();
+    assert!(depth(m, 0.5).abs() < 1e-6 && (depth(m, 50.0) - 1.0).abs() < 1e-6);
+    assert_eq!(projection.depth_clear(), 1.0);
+
+    projection.reverse_z = true;
+    let r = projection.matrix();
+    assert!((depth(r, 0.5) - 1.0).abs() < 1e-6 && depth(r, 50.0).abs() < 1e-6);
+    assert!((depth(r, 5.0) - (1.0 - depth(m, 5.0))).abs() < 1e-6);
+    assert_eq!(projection.depth_clear(), 0.0);
+    assert_eq!(
+        projection.depth_compare(),
+        wgpu::CompareFunction::GreaterEqual
+    );
+    assert_eq!(projection.depth_format(), wgpu::TextureFormat::Depth32Float);
+
+    // x and y are unaffected
+    let p = Point3::new(1.0, 2.0, -5.0);
+    let (a, b) = (m.transform_point(p), r.transform_point(p));
+    assert_eq!((a.x, a.y), (b.x, b.y));
+}
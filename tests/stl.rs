@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use hello_world::colormap::Colormap;
+use hello_world::mesh::{self, Mesh, NormalMode};
+use hello_world::stl::{self, StlFormat};
+use hello_world::{math_func, surface_data, vertex_data};
+
+// every directed edge of a closed, consistently oriented mesh is used exactly once, and its
+// reverse exactly once
+fn assert_closed_manifold(name: &str, mesh: &Mesh) {
+    let mut edges: HashMap<(u32, u32), usize> = HashMap::new();
+    for [a, b, c] in mesh.triangles() {
+        for edge in [(a, b), (b, c), (c, a)] {
+            *edges.entry(edge).or_default() += 1;
+        }
+    }
+    for (&(a, b), &count) in &edges {
+        assert_eq!(
+            count, 1,
+            "{}: edge {} -> {} is used {} times",
+            name, a, b, count
+        );
+        assert!(
+            edges.contains_key(&(b, a)),
+            "{}: edge {} -> {} is open",
+            name,
+            a,
+            b
+        );
+    }
+}
+
+// positive for outward facing triangles
+fn signed_volume(mesh: &Mesh) -> f32 {
+    mesh.triangles()
+        .iter()
+        .map(|tri| {
+            let [a, b, c] = tri.map(|i| mesh.positions[i as usize]);
+            (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                + a[2] * (b[0] * c[1] - b[1] * c[0]))
+                / 6.0
+        })
+        .sum()
+}
+
+#[test]
+fn open_surfaces_thicken_into_closed_shells() {
+    let jet = Colormap::default();
+    // a flat patch gives a box of the patch area times the thickness
+    let plane = Mesh {
+        positions: (0..81)
+            .map(|k| [(k / 9) as f32 / 4.0 - 1.0, 0.0, (k % 9) as f32 / 4.0 - 1.0])
+            .collect(),
+        indices: Some(mesh::grid_indices(9, 9, [(0, 0), (0, 1), (1, 1), (1, 0)])),
+        ..Default::default()
+    };
+    let shell = plane.thicken(0.1);
+    assert_closed_manifold("plane", &shell);
+    let volume = signed_volume(&shell);
+    assert!((volume.abs() - 4.0 * 0.1).abs() < 1e-3, "volume {}", volume);
+
+    let peaks = surface_data::simple_surface_data(
+        &math_func::peaks,
+        &jet,
+        -3.0,
+        3.0,
+        -3.0,
+        3.0,
+        21,
+        21,
+        2.0,
+        0.0,
+        NormalMode::Flat,
+    );
+    assert_closed_manifold("peaks", &peaks.thicken(0.05));
+
+    let wellenkugel = surface_data::parametric_surface_data(
+        &math_func::wellenkugel,
+        &jet,
+        0.0,
+        14.5,
+        0.0,
+        2.0 * PI,
+        40,
+        30,
+        -15.0,
+        15.0,
+        -15.0,
+        15.0,
+        2.0,
+        0.0,
+        NormalMode::Flat,
+    );
+    assert_closed_manifold("wellenkugel", &wellenkugel.thicken(0.05));
+
+    // the klein bottle is closed but one-sided: its flip seam (u = 0 against u = pi, which
+    // lines up on an even number of v steps) joins the two layers without any wall
+    let klein = surface_data::parametric_surface_data(
+        &math_func::klein_bottle,
+        &jet,
+        0.0,
+        PI,
+        0.0,
+        2.0 * PI,
+        40,
+        31,
+        -2.0,
+        2.0,
+        -2.0,
+        2.0,
+        2.0,
+        0.0,
+        NormalMode::Flat,
+    );
+    let shell = klein.thicken(0.05);
+    assert_closed_manifold("klein bottle", &shell);
+    assert_eq!(shell.triangles().len(), 2 * klein.triangles().len());
+
+    // a closed, two-sided surface gives two nested shells
+    let torus = vertex_data::torus_data_index(1.5, 0.4, 30, 20).thicken(0.1);
+    assert_closed_manifold("torus", &torus);
+    assert_eq!(torus.num_vertices(), 2 * 29 * 19);
+}
+
+#[test]
+fn stl_formats_hold_every_facet() {
+    let mesh = vertex_data::sphere_data_index(1.0, 8, 10);
+    let facets = mesh.triangles().len();
+
+    let mut binary = Vec::new();
+    stl::write_stl(&mesh, &mut binary, StlFormat::Binary).unwrap();
+    assert_eq!(binary.len(), 84 + 50 * facets);
+    assert!(!binary.starts_with(b"solid"));
+    assert_eq!(
+        u32::from_le_bytes(binary[80..84].try_into().unwrap()) as usize,
+        facets
+    );
+
+    let mut ascii = Vec::new();
+    stl::write_stl(&mesh, &mut ascii, StlFormat::Ascii).unwrap();
+    let text = String::from_utf8(ascii).unwrap();
+    assert!(text.starts_with("solid ") && text.trim_end().ends_with("endsolid hello_world"));
+    assert_eq!(text.matches("facet normal").count(), facets);
+    assert_eq!(text.matches("vertex ").count(), 3 * facets);
+}
@@ -0,0 +1,45 @@
+use hello_world::colormap::Colormap;
+use hello_world::mesh::NormalMode;
+use hello_world::surfaces::{self, Domain};
+
+#[test]
+fn every_surface_fits_its_box() {
+    for surface in surfaces::surfaces() {
+        let colormap = Colormap::from_name(surface.colormap).unwrap();
+        let mesh = surface.mesh_index(&colormap, NormalMode::AreaWeighted);
+        let [nu, nv] = surface.resolution;
+        assert_eq!(mesh.num_vertices(), nu * nv, "{}", surface.name);
+        for p in &mesh.positions {
+            assert!(
+                p.iter()
+                    .all(|c| c.is_finite() && c.abs() <= surface.scale * 1.001),
+                "{}: {:?} outside the box",
+                surface.name,
+                p
+            );
+        }
+        for n in &mesh.normals {
+            assert!(
+                n.iter().all(|c| c.is_finite()),
+                "{}: normal {:?}",
+                surface.name,
+                n
+            );
+        }
+    }
+}
+
+#[test]
+fn lookup_by_name() {
+    let klein = surfaces::find("klein_bottle").unwrap();
+    assert!(matches!(klein.domain, Domain::Parametric { .. }));
+    assert!(matches!(
+        surfaces::find("sinc").unwrap().domain,
+        Domain::Simple { .. }
+    ));
+    assert_eq!(surfaces::names().len(), surfaces::surfaces().len());
+
+    // the error lists the valid names
+    let e = surfaces::find("klein").err().unwrap().to_string();
+    assert!(e.starts_with("unknown surface 'klein', expected one of: sinc, peaks, klein_bottle"));
+}
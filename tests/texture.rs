@@ -0,0 +1,133 @@
+use hello_world::texture_data::{f32_to_f16, mip_level_count, ColorSpace, Texture, TextureOptions};
+use hello_world::{capture, transforms};
+
+#[test]
+fn mip_chains_and_anisotropy() {
+    assert_eq!(mip_level_count(256, 128), 9);
+    assert_eq!(mip_level_count(5, 3), 3);
+    assert_eq!(mip_level_count(1, 1), 1);
+
+    let clamp = |anisotropy| {
+        TextureOptions {
+            anisotropy,
+            ..Default::default()
+        }
+        .anisotropy_clamp()
+    };
+    assert_eq!(clamp(1), None);
+    assert_eq!(clamp(3).map(|a| a.get()), Some(2));
+    assert_eq!(clamp(16).map(|a| a.get()), Some(16));
+    assert_eq!(clamp(255).map(|a| a.get()), Some(16));
+}
+
+#[test]
+fn mip_levels_average_in_linear_space() {
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(64, 64, format));
+    // a black and white checkerboard of single pixels
+    let checker = image::RgbaImage::from_fn(4, 4, |x, y| {
+        let v = if (x + y) % 2 == 0 { 255 } else { 0 };
+        image::Rgba([v, v, v, 255])
+    });
+    let options = TextureOptions {
+        readable: true,
+        ..Default::default()
+    };
+    let (device, queue) = (&init.device, &init.queue);
+    let texture = Texture::from_image(device, queue, &init.mipmaps, &checker, &options);
+
+    // every texel below the first level is half the light of white, which is 188 in srgb
+    for (level, size) in [(1, 2), (2, 1)] {
+        let img = capture::capture_mip_level(
+            &init.device,
+            &init.queue,
+            &texture.texture,
+            level,
+            size,
+            size,
+            format,
+        )
+        .unwrap();
+        for pixel in img.pixels() {
+            assert!(
+                (pixel[0] as i32 - 188).abs() <= 2,
+                "level {}: {:?}",
+                level,
+                pixel
+            );
+        }
+    }
+}
+
+#[test]
+fn half_floats_round_to_nearest() {
+    assert_eq!(f32_to_f16(0.0), 0);
+    assert_eq!(f32_to_f16(-0.0), 0x8000);
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.5), 0xc100);
+    assert_eq!(f32_to_f16(65504.0), 0x7bff);
+    assert_eq!(f32_to_f16(1e6), 0x7c00);
+    assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+    assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+    // the smallest subnormal, and a value halfway between 1 and the next half, which rounds to
+    // the even 1
+    assert_eq!(f32_to_f16(2f32.powi(-24)), 1);
+    assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+    assert_eq!(f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+}
+
+#[test]
+fn images_of_any_format_load() {
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    let init = pollster::block_on(transforms::InitWgpu::init_headless(64, 64, format));
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let load = |img: image::DynamicImage, name: &str, color_space| {
+        let path = format!("{}/{}", dir, name);
+        img.save(&path).unwrap();
+        let options = TextureOptions {
+            color_space,
+            readable: true,
+            ..Default::default()
+        };
+        let (device, queue, mipmaps) = (&init.device, &init.queue, &init.mipmaps);
+        Texture::create_texture_with_options(device, queue, mipmaps, &path, &options).unwrap()
+    };
+
+    let rgb = image::RgbImage::from_fn(4, 4, |x, y| image::Rgb([x as u8 * 60, y as u8 * 60, 90]));
+    let gray = image::GrayImage::from_fn(4, 4, |x, _| image::Luma([x as u8 * 80]));
+    let deep = image::ImageBuffer::<image::Rgba<u16>, _>::from_pixel(4, 4, image::Rgba([65535; 4]));
+    let cases = [
+        (load(rgb.into(), "rgb8.png", ColorSpace::Srgb), format),
+        (
+            load(gray.into(), "luma8.png", ColorSpace::Linear),
+            wgpu::TextureFormat::Rgba8Unorm,
+        ),
+        (
+            load(deep.into(), "rgba16.png", ColorSpace::Srgb),
+            wgpu::TextureFormat::Rgba16Float,
+        ),
+    ];
+    for (texture, expected) in &cases {
+        assert_eq!(texture.format, *expected);
+    }
+
+    // 8 bit texels are uploaded as stored, with alpha added
+    let capture = |texture: &Texture| {
+        let (device, queue) = (&init.device, &init.queue);
+        capture::capture_mip_level(device, queue, &texture.texture, 0, 4, 4, texture.format)
+            .unwrap()
+    };
+    assert_eq!(capture(&cases[0].0).get_pixel(3, 1).0, [180, 60, 90, 255]);
+    assert_eq!(capture(&cases[1].0).get_pixel(2, 0).0, [160, 160, 160, 255]);
+
+    // a missing or broken file is an error rather than a panic
+    let options = TextureOptions::default();
+    let missing = format!("{}/missing.png", dir);
+    let broken = format!("{}/broken.png", dir);
+    std::fs::write(&broken, b"not an image").unwrap();
+    for path in [missing, broken] {
+        let (device, queue, mipmaps) = (&init.device, &init.queue, &init.mipmaps);
+        let result = Texture::create_texture_with_options(device, queue, mipmaps, &path, &options);
+        assert!(result.is_err(), "{}", path);
+    }
+}